@@ -21,7 +21,7 @@ impl<'q> quote::ToTokens for FieldConstruction<'q> {
                                     name: stringify!(#name).to_string(),
                                     ty: stringify!(#ty).to_string(),
                                 })
-                            .and_then(#ty::from_config_element)?
+                            .and_then(<#ty>::from_config_element)?
                         },
                     }
                 });
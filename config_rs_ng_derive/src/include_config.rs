@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use proc_macro::TokenStream as TS;
+use proc_macro_error::abort;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::{parse_macro_input, Ident, LitStr, Token, Type};
+
+struct IncludeConfig {
+    path: LitStr,
+    format: Option<Ident>,
+    check: Option<Type>,
+}
+
+impl Parse for IncludeConfig {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut format = None;
+        let mut check = None;
+
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "format" => format = Some(input.parse()?),
+                "check" => check = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "Expected `format = ...` or `check = ...`",
+                    ))
+                }
+            }
+        }
+
+        if !input.is_empty() {
+            return Err(input.error("Unexpected input"));
+        }
+
+        Ok(IncludeConfig {
+            path,
+            format,
+            check,
+        })
+    }
+}
+
+/// The position of a syntax error in a file
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    #[cfg(feature = "toml")]
+    fn from_offset(content: &str, offset: usize) -> Self {
+        let before = &content[..offset.min(content.len())];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// Parse `content` with `format`, returning the path of the format parser on success
+///
+/// The parsers are the ones `TomlFormatParser` and `JsonFormatParser` use at runtime (`toml` and
+/// `serde_json` are the same versions in both crates), so that a file that is accepted here also
+/// loads.
+#[cfg_attr(not(any(feature = "json", feature = "toml")), allow(unused_variables))]
+fn check_syntax(
+    format: &Ident,
+    content: &str,
+) -> Result<proc_macro2::TokenStream, (Option<Position>, String)> {
+    match format.to_string().as_str() {
+        #[cfg(feature = "toml")]
+        "toml" => toml::from_str::<toml::Value>(content)
+            .map(|_| quote::quote! { config_rs_ng::TomlFormatParser })
+            .map_err(|e| {
+                let position = e.span().map(|span| Position::from_offset(content, span.start));
+                (position, e.message().to_string())
+            }),
+
+        #[cfg(feature = "json")]
+        "json" => serde_json::from_str::<serde_json::Value>(content)
+            .map(|_| quote::quote! { config_rs_ng::JsonFormatParser })
+            .map_err(|e| {
+                let position = Position {
+                    line: e.line(),
+                    column: e.column(),
+                };
+                let message = e.to_string();
+                let message = match message.rsplit_once(" at line ") {
+                    Some((message, _)) => message.to_string(),
+                    None => message,
+                };
+                (Some(position), message)
+            }),
+
+        other => abort!(
+            format,
+            "Unsupported format '{}', the corresponding feature of config_rs_ng may not be enabled",
+            other
+        ),
+    }
+}
+
+pub fn include_config_impl(input: TS) -> TS {
+    let input = parse_macro_input!(input as IncludeConfig);
+
+    let path = PathBuf::from(input.path.value());
+    let path = if path.is_relative() {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+            .unwrap_or_else(|_| abort!(input.path, "CARGO_MANIFEST_DIR is not set"));
+        PathBuf::from(manifest_dir).join(path)
+    } else {
+        path
+    };
+
+    let format = match input.format {
+        Some(format) => format,
+        None => match path.extension().and_then(|e| e.to_str()) {
+            Some(ext @ ("json" | "toml")) => Ident::new(ext, input.path.span()),
+            _ => abort!(
+                input.path,
+                "Cannot detect the format of the file, specify it with `format = ...`"
+            ),
+        },
+    };
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => abort!(input.path, "Failed to read '{}': {}", path.display(), e),
+    };
+
+    let parser = match check_syntax(&format, &content) {
+        Ok(parser) => parser,
+        Err((Some(position), message)) => abort!(
+            input.path,
+            "{}:{}:{}: {}",
+            path.display(),
+            position.line,
+            position.column,
+            message
+        ),
+        Err((None, message)) => abort!(input.path, "{}: {}", path.display(), message),
+    };
+
+    let path = match path.to_str() {
+        Some(path) => path.to_string(),
+        None => abort!(input.path, "The path '{}' is not valid UTF-8", path.display()),
+    };
+
+    let check = input.check.map(|ty| {
+        quote::quote! { .validate::<#ty>() }
+    });
+
+    let expanded = quote::quote! {
+        config_rs_ng::StringSource::<#parser>::included(std::include_str!(#path), #path) #check
+    };
+
+    TS::from(expanded)
+}
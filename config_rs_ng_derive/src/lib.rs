@@ -2,6 +2,7 @@ use proc_macro::TokenStream as TS;
 
 mod config_constructor;
 mod from_config_element;
+mod include_config;
 
 #[proc_macro_derive(ConfigConstructor)]
 pub fn derive_config_constructor(input: TS) -> TS {
@@ -13,3 +14,11 @@ pub fn derive_from_config_element(input: TS) -> TS {
     crate::from_config_element::derive_from_config_element_impl(input)
 }
 
+/// Embed a configuration file in the binary, checking its syntax at compile time
+///
+/// See the documentation of `config_rs_ng::include_config!`.
+#[proc_macro]
+#[proc_macro_error::proc_macro_error]
+pub fn include_config(input: TS) -> TS {
+    crate::include_config::include_config_impl(input)
+}
@@ -36,6 +36,10 @@ impl ParsableAccessor for String {
 ///
 /// An instance of this type can be used to access a configuration value from a [Layers] instance
 /// (that one retrieves via [Config::layers]).
+///
+/// An accessor is displayed in the syntax of the [ParsableAccessor] implementation for `&str`,
+/// e.g. `foo.5.bar`.
+#[derive(Clone, Debug)]
 pub struct Accessor {
     stack: Vec<AccessType>,
     index: usize,
@@ -47,20 +51,51 @@ impl Accessor {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum AccessType {
     Key(String),
     Index(usize),
 }
 
 impl Accessor {
+    /// All steps of the accessor
+    pub fn steps(&self) -> &[AccessType] {
+        &self.stack
+    }
+
     pub(crate) fn current(&self) -> Option<&AccessType> {
         self.stack.get(self.index)
     }
 
+    /// The steps of the accessor that were not yet taken
+    pub(crate) fn remaining(&self) -> &[AccessType] {
+        &self.stack[self.index.min(self.stack.len())..]
+    }
+
+    /// Start over at the first step
+    pub(crate) fn reset(&mut self) {
+        self.index = 0;
+    }
+
     pub(crate) fn advance(&mut self) {
         self.index += 1;
     }
 }
 
+impl std::fmt::Display for Accessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, step) in self.stack.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            match step {
+                AccessType::Key(key) => write!(f, "{}", key)?,
+                AccessType::Index(index) => write!(f, "{}", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AccessorParseError {}
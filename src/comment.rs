@@ -0,0 +1,27 @@
+use crate::path_map::PathMap;
+
+/// The comments attached to a value in the document it was parsed from
+///
+/// The comment markers (e.g. `#`) and the whitespace around the text are removed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Comment {
+    /// The comment lines directly before the value (or its key), joined with newlines
+    pub leading: Option<String>,
+
+    /// The comment after the value on the same line
+    pub trailing: Option<String>,
+}
+
+impl Comment {
+    pub fn new(leading: Option<String>, trailing: Option<String>) -> Self {
+        Comment { leading, trailing }
+    }
+}
+
+/// The comments of the values of a parsed document, by their position in the document
+///
+/// A [FormatParser](crate::FormatParser) records the comments of the values it parses in
+/// [FormatParser::parse_with_comments](crate::FormatParser::parse_with_comments). They are
+/// returned from [ConfigView::comment](crate::ConfigView::comment).
+pub type Comments = PathMap<Comment>;
@@ -5,7 +5,9 @@ use crate::{
     AsyncConfig,
 };
 
+use super::decrypt::decrypt_values;
 use super::ConfigError;
+use super::ValueCipher;
 use crate::config::layers::Layers;
 
 #[derive(Debug)]
@@ -13,6 +15,7 @@ pub struct AsyncConfigBuilder {
     layers_builders: Vec<Box<dyn crate::source::AsyncConfigSource>>,
     defaults_builders: Vec<Box<dyn crate::source::AsyncConfigSource>>,
     overwrites_builders: Vec<Box<dyn crate::source::AsyncConfigSource>>,
+    ciphers: Vec<Box<dyn ValueCipher>>,
 }
 
 impl AsyncConfigBuilder {
@@ -21,6 +24,7 @@ impl AsyncConfigBuilder {
             layers_builders: Vec::new(),
             defaults_builders: Vec::new(),
             overwrites_builders: Vec::new(),
+            ciphers: Vec::new(),
         }
     }
 
@@ -39,6 +43,14 @@ impl AsyncConfigBuilder {
         self
     }
 
+    /// Decrypt inline encrypted values (`ENC[<scheme>:<data>]`) with `cipher`
+    ///
+    /// See [ConfigBuilder::decrypt_values](crate::ConfigBuilder::decrypt_values).
+    pub fn decrypt_values(mut self, cipher: Box<dyn ValueCipher>) -> Self {
+        self.ciphers.push(cipher);
+        self
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument)]
     pub async fn build(self) -> Result<AsyncConfig, ConfigError> {
         AsyncConfig::build_from_builder(self).await
@@ -91,6 +103,7 @@ impl AsyncConfigBuilder {
             .into_iter()
             .chain(layers.into_iter())
             .chain(defaults.into_iter())
+            .map(|object| decrypt_values(object?, &self.ciphers))
             .collect::<Result<Layers, _>>()
     }
 }
@@ -2,7 +2,9 @@ use crate::config::Config;
 use crate::source::ConfigSource;
 use crate::source::SourceError;
 
+use super::decrypt::decrypt_values;
 use super::ConfigError;
+use super::ValueCipher;
 use crate::config::layers::Layers;
 
 #[derive(Debug)]
@@ -10,6 +12,7 @@ pub struct ConfigBuilder {
     layers_builders: Vec<Box<dyn ConfigSource>>,
     defaults_builders: Vec<Box<dyn ConfigSource>>,
     overwrites_builders: Vec<Box<dyn ConfigSource>>,
+    ciphers: Vec<Box<dyn ValueCipher>>,
 }
 
 impl ConfigBuilder {
@@ -18,6 +21,7 @@ impl ConfigBuilder {
             layers_builders: Vec::new(),
             defaults_builders: Vec::new(),
             overwrites_builders: Vec::new(),
+            ciphers: Vec::new(),
         }
     }
 
@@ -36,6 +40,16 @@ impl ConfigBuilder {
         self
     }
 
+    /// Decrypt inline encrypted values (`ENC[<scheme>:<data>]`) with `cipher`
+    ///
+    /// Each scheme is handled by the cipher registered for it. Once a cipher is registered,
+    /// loading fails for encrypted values of schemes without a cipher. Decrypted values are
+    /// strings.
+    pub fn decrypt_values(mut self, cipher: Box<dyn ValueCipher>) -> Self {
+        self.ciphers.push(cipher);
+        self
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument)]
     pub fn build(self) -> Result<Config, ConfigError> {
         Config::build_from_builder(self)
@@ -48,6 +62,7 @@ impl ConfigBuilder {
             .map(|cs| cs.load())
             .chain(self.layers_builders.iter().map(|cs| cs.load()))
             .chain(self.defaults_builders.iter().map(|cs| cs.load()))
+            .map(|object| decrypt_values(object?, &self.ciphers))
             .collect()
     }
 }
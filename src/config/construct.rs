@@ -1,7 +1,12 @@
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::element::ConfigElementType;
+use crate::element::Value;
 use crate::ConfigElement;
+use crate::Datetime;
+use crate::DatetimeParseError;
 
 use super::{ConfigError, Layers};
 
@@ -40,6 +45,21 @@ pub enum FromConfigElementError {
 
     #[error(transparent)]
     ConfigError(#[from] ConfigError),
+
+    #[error(transparent)]
+    DatetimeParseError(#[from] DatetimeParseError),
+
+    #[error("Failed to decode {encoding} string: {message}")]
+    DecodeError {
+        encoding: &'static str,
+        message: String,
+    },
+
+    #[error("Datetime '{datetime}' cannot be represented as {ty}")]
+    UnrepresentableDatetime {
+        datetime: Datetime,
+        ty: &'static str,
+    },
 }
 
 macro_rules! impl_from_config_element {
@@ -65,10 +85,12 @@ impl_from_config_element!(i8, as_i8, "i8");
 impl_from_config_element!(i16, as_i16, "i16");
 impl_from_config_element!(i32, as_i32, "i32");
 impl_from_config_element!(i64, as_i64, "i64");
+impl_from_config_element!(i128, as_i128, "i128");
 impl_from_config_element!(u8, as_u8, "u8");
 impl_from_config_element!(u16, as_u16, "u16");
 impl_from_config_element!(u32, as_u32, "u32");
 impl_from_config_element!(u64, as_u64, "u64");
+impl_from_config_element!(u128, as_u128, "u128");
 impl_from_config_element!(f32, as_f32, "f32");
 impl_from_config_element!(f64, as_f64, "f64");
 
@@ -86,6 +108,156 @@ impl FromConfigElement for String {
     }
 }
 
+/// Copies the element with [to_owned_value](trait.ConfigElement.html#method.to_owned_value)
+impl FromConfigElement for Value {
+    type Error = FromConfigElementError;
+
+    fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        element.to_owned_value()
+    }
+}
+
+/// Native datetimes are used as they are, strings are parsed as RFC 3339
+impl FromConfigElement for Datetime {
+    type Error = FromConfigElementError;
+
+    fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        if let Some(datetime) = element.as_datetime() {
+            return Ok(datetime);
+        }
+
+        match element.as_str() {
+            Some(s) => s.parse().map_err(FromConfigElementError::from),
+            None => Err(FromConfigElementError::TypeError {
+                expected: "datetime",
+                found: element.get_type().name(),
+            }),
+        }
+    }
+}
+
+/// Implement [FromConfigElement] for a datetime type of another crate
+///
+/// The value is constructed from a [Datetime] of type `$kind` with `$convert`, which returns
+/// `None` if the value cannot be represented by `$t`.
+#[cfg(any(feature = "chrono", feature = "time"))]
+macro_rules! impl_from_config_element_for_datetime {
+    ($t:ty, $kind:expr, $convert:expr) => {
+        impl FromConfigElement for $t {
+            type Error = FromConfigElementError;
+
+            fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+                let datetime = Datetime::from_config_element(element)?;
+                let expected: ConfigElementType = $kind;
+                let found = datetime.element_type();
+                if found.name() != expected.name() {
+                    return Err(FromConfigElementError::TypeError {
+                        expected: expected.name(),
+                        found: found.name(),
+                    });
+                }
+
+                let convert: fn(&Datetime) -> Option<$t> = $convert;
+                convert(&datetime).ok_or(FromConfigElementError::UnrepresentableDatetime {
+                    datetime,
+                    ty: std::any::type_name::<$t>(),
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn date(datetime: &Datetime) -> Option<chrono::NaiveDate> {
+        let date = datetime.date?;
+        chrono::NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+    }
+
+    fn time(datetime: &Datetime) -> Option<chrono::NaiveTime> {
+        let time = datetime.time?;
+        // chrono represents leap seconds as a nanosecond value above one second
+        let (second, nanosecond) = match time.second {
+            60 => (59, time.nanosecond + 1_000_000_000),
+            second => (second.into(), time.nanosecond),
+        };
+        chrono::NaiveTime::from_hms_nano_opt(
+            time.hour.into(),
+            time.minute.into(),
+            second,
+            nanosecond,
+        )
+    }
+
+    fn date_time(datetime: &Datetime) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let offset = chrono::FixedOffset::east_opt(i32::from(datetime.offset?.minutes()) * 60)?;
+        let local = chrono::NaiveDateTime::new(date(datetime)?, time(datetime)?);
+        offset.from_local_datetime(&local).single()
+    }
+
+    impl_from_config_element_for_datetime!(
+        chrono::DateTime<chrono::FixedOffset>,
+        ConfigElementType::OffsetDatetime,
+        date_time
+    );
+    impl_from_config_element_for_datetime!(
+        chrono::DateTime<chrono::Utc>,
+        ConfigElementType::OffsetDatetime,
+        |datetime| date_time(datetime).map(|dt| dt.with_timezone(&chrono::Utc))
+    );
+    impl_from_config_element_for_datetime!(
+        chrono::NaiveDateTime,
+        ConfigElementType::LocalDatetime,
+        |datetime| Some(chrono::NaiveDateTime::new(date(datetime)?, time(datetime)?))
+    );
+    impl_from_config_element_for_datetime!(chrono::NaiveDate, ConfigElementType::LocalDate, date);
+    impl_from_config_element_for_datetime!(chrono::NaiveTime, ConfigElementType::LocalTime, time);
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use super::*;
+
+    fn date(datetime: &Datetime) -> Option<time::Date> {
+        let date = datetime.date?;
+        let month = time::Month::try_from(date.month).ok()?;
+        time::Date::from_calendar_date(date.year.into(), month, date.day).ok()
+    }
+
+    fn time(datetime: &Datetime) -> Option<time::Time> {
+        let t = datetime.time?;
+        time::Time::from_hms_nano(t.hour, t.minute, t.second, t.nanosecond).ok()
+    }
+
+    fn date_time(datetime: &Datetime) -> Option<time::PrimitiveDateTime> {
+        Some(time::PrimitiveDateTime::new(
+            date(datetime)?,
+            time(datetime)?,
+        ))
+    }
+
+    impl_from_config_element_for_datetime!(
+        time::OffsetDateTime,
+        ConfigElementType::OffsetDatetime,
+        |datetime| {
+            let seconds = i32::from(datetime.offset?.minutes()) * 60;
+            let offset = time::UtcOffset::from_whole_seconds(seconds).ok()?;
+            Some(date_time(datetime)?.assume_offset(offset))
+        }
+    );
+    impl_from_config_element_for_datetime!(
+        time::PrimitiveDateTime,
+        ConfigElementType::LocalDatetime,
+        date_time
+    );
+    impl_from_config_element_for_datetime!(time::Date, ConfigElementType::LocalDate, date);
+    impl_from_config_element_for_datetime!(time::Time, ConfigElementType::LocalTime, time);
+}
+
 impl<T> FromConfigElement for Option<T>
 where
     T: FromConfigElement<Error = FromConfigElementError>,
@@ -107,7 +279,15 @@ where
 {
     type Error = FromConfigElementError;
 
+    /// Native bytes are converted byte by byte, so a `Vec<u8>` can be constructed from them
     fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        if let Some(bytes) = element.as_bytes() {
+            return bytes
+                .iter()
+                .map(|byte| T::from_config_element(&Value::from(*byte)))
+                .collect();
+        }
+
         let list = element.as_list().ok_or_else(|| {
             let found = element.get_type().name();
             FromConfigElementError::TypeError {
@@ -116,11 +296,19 @@ where
             }
         })?;
 
-        let mut v = Vec::with_capacity(list.len());
-        for index in 0..list.len() {
-            v.push(T::from_config_element(list.at_index(index).unwrap())?);
+        list.iter().map(T::from_config_element).collect()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromConfigElement for bytes::Bytes {
+    type Error = FromConfigElementError;
+
+    fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        match element.as_bytes() {
+            Some(bytes) => Ok(bytes::Bytes::copy_from_slice(bytes)),
+            None => Vec::<u8>::from_config_element(element).map(bytes::Bytes::from),
         }
-        Ok(v)
     }
 }
 
@@ -139,9 +327,8 @@ where
             }
         })?;
 
-        map.keys()
-            .into_iter()
-            .map(|key| T::from_config_element(map.get(&key).unwrap()).map(|val| (key, val)))
+        map.entries()
+            .map(|(key, element)| T::from_config_element(element).map(|val| (key.to_string(), val)))
             .collect::<Result<HashMap<String, T>, _>>()
     }
 }
@@ -161,9 +348,8 @@ where
             }
         })?;
 
-        map.keys()
-            .into_iter()
-            .map(|key| T::from_config_element(map.get(&key).unwrap()).map(|val| (key, val)))
+        map.entries()
+            .map(|(key, element)| T::from_config_element(element).map(|val| (key.to_string(), val)))
             .collect::<Result<BTreeMap<String, T>, _>>()
     }
 }
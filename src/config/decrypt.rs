@@ -0,0 +1,329 @@
+//! Decrypting inline encrypted values of loaded configuration layers
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::description::ConfigSourceDescription;
+use crate::element::ConfigElement;
+use crate::element::ConfigElementCustomType;
+use crate::element::ConfigElementListType;
+use crate::element::ConfigElementMapType;
+use crate::element::Datetime;
+use crate::element::Number;
+use crate::element::Shared;
+use crate::element::Value;
+use crate::object::ConfigObject;
+use crate::source::SourceError;
+
+const ENCRYPTED_PREFIX: &str = "ENC[";
+const ENCRYPTED_SUFFIX: &str = "]";
+
+/// A cipher that decrypts inline encrypted values
+///
+/// String values of the form `ENC[<scheme>:<data>]` are decrypted with the cipher whose
+/// [ValueCipher::scheme] is `<scheme>`. Ciphers are registered on the builder with
+/// [ConfigBuilder::decrypt_values](crate::ConfigBuilder::decrypt_values), and values are decrypted
+/// each time the configuration is (re)loaded.
+pub trait ValueCipher: std::fmt::Debug + Send + Sync {
+    /// The name of the scheme that is handled by this cipher, e.g. `age`
+    fn scheme(&self) -> &str;
+
+    /// Decrypt `data`, the part of an encrypted value after `ENC[<scheme>:` and before `]`
+    fn decrypt(&self, data: &str) -> Result<String, SourceError>;
+}
+
+/// Decrypt all inline encrypted values of `object`
+///
+/// Returns `object` unchanged if no ciphers are given or if it contains no encrypted values.
+pub(crate) fn decrypt_values(
+    object: ConfigObject,
+    ciphers: &[Box<dyn ValueCipher>],
+) -> Result<ConfigObject, SourceError> {
+    if ciphers.is_empty() {
+        return Ok(object);
+    }
+
+    object.try_map_element(|element| {
+        let root: Arc<dyn ConfigElement> = Arc::from(element);
+        Ok(match Decrypted::resolve(ciphers, &root, &*root, &[])? {
+            Some(decrypted) => Box::new(decrypted),
+            None => Box::new(Shared(root)),
+        })
+    })
+}
+
+/// A step on the path from the root of a layer to one of its elements
+#[derive(Debug, Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// A decrypted value, or a map or list that contains decrypted values further down
+#[derive(Debug)]
+enum Decrypted {
+    Value(Value),
+    Overlay(Overlay),
+}
+
+impl Decrypted {
+    /// Decrypt the values in `element`, which is found at `path` in `root`
+    ///
+    /// Returns `None` if there are no encrypted values in `element`.
+    fn resolve(
+        ciphers: &[Box<dyn ValueCipher>],
+        root: &Arc<dyn ConfigElement>,
+        element: &dyn ConfigElement,
+        path: &[Step],
+    ) -> Result<Option<Self>, SourceError> {
+        if let Some(s) = element.as_str() {
+            return decrypt_str(ciphers, s, path).map(|value| value.map(Decrypted::Value));
+        }
+
+        let child_path = |step| {
+            let mut child_path = path.to_vec();
+            child_path.push(step);
+            child_path
+        };
+
+        let mut keys = BTreeMap::new();
+        let mut indices = BTreeMap::new();
+        if let Some(map) = element.as_map() {
+            for (key, value) in map.entries() {
+                let path = child_path(Step::Key(key.to_string()));
+                if let Some(decrypted) = Self::resolve(ciphers, root, value, &path)? {
+                    keys.insert(key.to_string(), decrypted);
+                }
+            }
+        } else if let Some(list) = element.as_list() {
+            for (index, value) in list.iter().enumerate() {
+                let path = child_path(Step::Index(index));
+                if let Some(decrypted) = Self::resolve(ciphers, root, value, &path)? {
+                    indices.insert(index, decrypted);
+                }
+            }
+        }
+
+        if keys.is_empty() && indices.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Decrypted::Overlay(Overlay {
+            root: root.clone(),
+            path: path.to_vec(),
+            keys,
+            indices,
+        })))
+    }
+}
+
+/// Decrypt `s` if it is an encrypted value
+fn decrypt_str(
+    ciphers: &[Box<dyn ValueCipher>],
+    s: &str,
+    path: &[Step],
+) -> Result<Option<Value>, SourceError> {
+    let encrypted = match s
+        .strip_prefix(ENCRYPTED_PREFIX)
+        .and_then(|s| s.strip_suffix(ENCRYPTED_SUFFIX))
+    {
+        Some(encrypted) => encrypted,
+        None => return Ok(None),
+    };
+
+    let (scheme, data) = encrypted.split_once(':').unwrap_or((encrypted, ""));
+    let cipher = ciphers
+        .iter()
+        .find(|cipher| cipher.scheme() == scheme)
+        .ok_or_else(|| SourceError::UnknownCipher(scheme.to_string()))?;
+
+    let plaintext = cipher
+        .decrypt(data)
+        .map_err(|source| SourceError::DecryptValue {
+            key: path
+                .iter()
+                .map(|step| match step {
+                    Step::Key(key) => key.clone(),
+                    Step::Index(index) => index.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("."),
+            source: Box::new(source),
+        })?;
+    Ok(Some(Value::Str(plaintext)))
+}
+
+/// A map or list of a layer that contains decrypted values, directly or further down
+///
+/// The map or list is identified by its path from the root of the layer, as it cannot be borrowed
+/// next to the root.
+#[derive(Debug)]
+struct Overlay {
+    root: Arc<dyn ConfigElement>,
+    path: Vec<Step>,
+    keys: BTreeMap<String, Decrypted>,
+    indices: BTreeMap<usize, Decrypted>,
+}
+
+impl Overlay {
+    /// The element of the layer this overlay replaces
+    fn inner(&self) -> Option<&dyn ConfigElement> {
+        let mut element = &*self.root;
+        for step in self.path.iter() {
+            element = match step {
+                Step::Key(key) => element.as_map()?.get(key)?,
+                Step::Index(index) => element.as_list()?.at_index(*index)?,
+            };
+        }
+        Some(element)
+    }
+}
+
+impl ConfigElementMapType for Overlay {
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        match self.keys.get(key) {
+            Some(decrypted) => Some(decrypted),
+            None => self.inner()?.as_map()?.get(key),
+        }
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        let inner = self.inner().and_then(|inner| inner.as_map());
+        Box::new(
+            inner
+                .into_iter()
+                .flat_map(|inner| inner.entries())
+                .map(|(key, value)| match self.keys.get(key) {
+                    Some(decrypted) => (key, decrypted as &dyn ConfigElement),
+                    None => (key, value),
+                }),
+        )
+    }
+}
+
+impl ConfigElementListType for Overlay {
+    fn len(&self) -> usize {
+        self.inner()
+            .and_then(|inner| inner.as_list())
+            .map(|inner| inner.len())
+            .unwrap_or_default()
+    }
+
+    fn at_index(&self, index: usize) -> Option<&dyn ConfigElement> {
+        match self.indices.get(&index) {
+            Some(decrypted) => Some(decrypted),
+            None => self.inner()?.as_list()?.at_index(index),
+        }
+    }
+}
+
+macro_rules! delegate_to_value {
+    ($($fun:ident -> $t:ty),* $(,)?) => {
+        $(
+            fn $fun(&self) -> Option<$t> {
+                match self {
+                    Decrypted::Value(v) => v.$fun(),
+                    Decrypted::Overlay(_) => None,
+                }
+            }
+        )*
+    };
+}
+
+impl ConfigElement for Decrypted {
+    delegate_to_value! {
+        as_bool -> bool,
+        as_number -> Number,
+        as_str -> &str,
+        as_bytes -> &[u8],
+        as_datetime -> Datetime,
+        as_custom -> &dyn ConfigElementCustomType,
+    }
+
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        match self {
+            Decrypted::Overlay(o) if !o.indices.is_empty() => Some(o),
+            _ => None,
+        }
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        match self {
+            Decrypted::Overlay(o) if !o.keys.is_empty() => Some(o),
+            _ => None,
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        false
+    }
+
+    fn source_description(&self) -> Option<&ConfigSourceDescription> {
+        match self {
+            Decrypted::Value(_) => None,
+            Decrypted::Overlay(o) => o.inner()?.source_description(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    /// A "cipher" that reverses the data, for testing
+    #[derive(Debug)]
+    struct Reverse;
+
+    impl ValueCipher for Reverse {
+        fn scheme(&self) -> &str {
+            "rev"
+        }
+
+        fn decrypt(&self, data: &str) -> Result<String, SourceError> {
+            Ok(data.chars().rev().collect())
+        }
+    }
+
+    fn source(
+        json: serde_json::Value,
+    ) -> Box<crate::source::test_source::TestSource<serde_json::Value>> {
+        Box::new(crate::source::test_source::TestSource(json))
+    }
+
+    #[test]
+    fn test_decrypt_values() {
+        let json = serde_json::json!({
+            "database": { "user": "app", "password": "ENC[rev:2retnuh]" },
+            "hosts": ["a", "ENC[rev:b]"],
+        });
+        let config = Config::builder()
+            .load(source(json))
+            .decrypt_values(Box::new(Reverse))
+            .build()
+            .unwrap();
+
+        let layers = config.layers();
+        let password = layers.get("database.password").unwrap().unwrap();
+        assert_eq!(password.as_str(), Some("hunter2"));
+        let user = layers.get("database.user").unwrap().unwrap();
+        assert_eq!(user.as_str(), Some("app"));
+        let hosts = layers.get("hosts").unwrap().unwrap();
+        let hosts: Vec<String> = crate::FromConfigElement::from_config_element(hosts).unwrap();
+        assert_eq!(hosts, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_unknown_cipher() {
+        let json = serde_json::json!({ "password": "ENC[other:xyz]" });
+        let err = Config::builder()
+            .load(source(json))
+            .decrypt_values(Box::new(Reverse))
+            .build()
+            .unwrap_err();
+        assert!(std::matches!(
+            err,
+            crate::ConfigError::SourceError(SourceError::UnknownCipher(scheme)) if scheme == "other"
+        ));
+    }
+}
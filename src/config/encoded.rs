@@ -0,0 +1,110 @@
+use crate::ConfigElement;
+
+use super::FromConfigElement;
+use super::FromConfigElementError;
+
+/// Binary data, decoded from a hex string in text formats
+///
+/// Formats without a binary type (like JSON or TOML) carry binary data as encoded strings. Use
+/// this type as a member of a [FromConfigElement] type to opt into decoding them. Native bytes
+/// ([ConfigElement::as_bytes]) and lists of bytes are used as they are.
+///
+/// `T` is constructed from the decoded `Vec<u8>`, e.g. `Hex<bytes::Bytes>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Hex<T = Vec<u8>>(pub T);
+
+/// Binary data, decoded from a base64 string in text formats
+///
+/// Strings are decoded with the standard alphabet and padding (RFC 4648). See [Hex] for the
+/// handling of other values.
+#[cfg(feature = "base64")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base64<T = Vec<u8>>(pub T);
+
+/// Get the bytes of `element`, decoding strings with `decode`
+fn decode<T, E>(
+    element: &dyn ConfigElement,
+    encoding: &'static str,
+    decode: impl FnOnce(&str) -> Result<Vec<u8>, E>,
+) -> Result<T, FromConfigElementError>
+where
+    T: From<Vec<u8>>,
+    E: std::fmt::Display,
+{
+    let bytes = match element.as_str() {
+        Some(s) => decode(s).map_err(|e| FromConfigElementError::DecodeError {
+            encoding,
+            message: e.to_string(),
+        })?,
+        None => Vec::<u8>::from_config_element(element).map_err(|e| match e {
+            FromConfigElementError::TypeError { found, .. } => FromConfigElementError::TypeError {
+                expected: encoding,
+                found,
+            },
+            e => e,
+        })?,
+    };
+    Ok(T::from(bytes))
+}
+
+impl<T> FromConfigElement for Hex<T>
+where
+    T: From<Vec<u8>>,
+{
+    type Error = FromConfigElementError;
+
+    fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        decode(element, "hex", decode_hex).map(Hex)
+    }
+}
+
+#[cfg(feature = "base64")]
+impl<T> FromConfigElement for Base64<T>
+where
+    T: From<Vec<u8>>,
+{
+    type Error = FromConfigElementError;
+
+    fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        use base64::Engine;
+
+        decode(element, "base64", |s| {
+            base64::engine::general_purpose::STANDARD.decode(s)
+        })
+        .map(Base64)
+    }
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, String> {
+    let pairs = s.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err("odd number of digits".to_string());
+    }
+
+    pairs
+        .map(|pair| {
+            let digit = |d: u8| (d as char).to_digit(16);
+            match (digit(pair[0]), digit(pair[1])) {
+                (Some(high), Some(low)) => Ok((high * 16 + low) as u8),
+                _ => Err(format!(
+                    "invalid digits '{}'",
+                    String::from_utf8_lossy(pair)
+                )),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("").unwrap(), Vec::<u8>::new());
+        assert_eq!(decode_hex("00Ff7a").unwrap(), vec![0x00, 0xff, 0x7a]);
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("0g").is_err());
+        assert!(decode_hex("+1").is_err());
+    }
+}
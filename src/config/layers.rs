@@ -1,6 +1,9 @@
 use crate::accessor::Accessor;
+use crate::config::ConfigConstructor;
 use crate::config::ConfigError;
+use crate::description::ConfigSourceDescription;
 use crate::element::ConfigElement;
+use crate::element::Walk;
 use crate::object::ConfigObject;
 use crate::object::ConfigView;
 use crate::ParsableAccessor;
@@ -22,6 +25,42 @@ impl FromIterator<ConfigObject> for Layers {
 }
 
 impl Layers {
+    /// Construct `T` from `object` alone, and give `object` back if that succeeds
+    pub(crate) fn check<T: ConfigConstructor>(
+        object: ConfigObject,
+    ) -> Result<ConfigObject, T::Error> {
+        let layers = Layers(vec![object]);
+        T::construct_from(&layers)?;
+
+        let Layers(mut objects) = layers;
+        Ok(objects.remove(0))
+    }
+
+    /// The descriptions of the sources of all layers, from the lowest to the highest priority
+    ///
+    /// This can be used to detect layers that were loaded from a cache, see
+    /// [CachedSource](crate::CachedSource).
+    pub fn descriptions(&self) -> impl Iterator<Item = &ConfigSourceDescription> {
+        self.0.iter().map(ConfigObject::description)
+    }
+
+    /// Walk the merged view of all layers depth-first
+    ///
+    /// Every element is yielded with the value [Layers::get] returns for its path: maps of
+    /// different layers are merged, all other values are taken from the highest layer that
+    /// contains them. See [Walk].
+    pub fn walk(&self) -> Walk<'_> {
+        Walk::new(self.0.iter().rev().map(ConfigObject::element).collect())
+    }
+
+    /// Walk all values of the merged view of all layers that are neither maps nor lists
+    ///
+    /// See [Layers::walk] and [flatten](trait.ConfigElement.html#method.flatten).
+    pub fn flatten(&self) -> impl Iterator<Item = (Accessor, &dyn ConfigElement)> {
+        self.walk()
+            .filter(|(_, element)| !element.is_map() && !element.is_list())
+    }
+
     /// Access the configuration at a specific position
     ///
     /// Use an object of a type implementing the `ParsableAccessor` trait for accessing the
@@ -84,6 +123,7 @@ impl Layers {
         mut accessor: Accessor,
     ) -> Result<Option<&dyn ConfigElement>, ConfigError> {
         for layer in self.0.iter().rev() {
+            accessor.reset();
             if let Some(value) = layer.get(&mut accessor)? {
                 return Ok(Some(value));
             }
@@ -101,6 +141,7 @@ impl Layers {
         mut accessor: Accessor,
     ) -> Result<Option<ConfigView<'_>>, ConfigError> {
         for layer in self.0.iter().rev() {
+            accessor.reset();
             if let Some(view) = layer.get_with_description(&mut accessor)? {
                 return Ok(Some(view));
             }
@@ -114,6 +155,7 @@ impl Layers {
         T: ConfigElement,
     {
         for layer in self.0.iter().rev() {
+            accessor.reset();
             if let Some(value) = layer.get_as::<T>(&mut accessor)? {
                 return Ok(Some(value));
             }
@@ -122,3 +164,30 @@ impl Layers {
         Ok(None)
     }
 }
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_falls_through_partial_match() {
+        let layer = |value: serde_json::Value| {
+            let desc = ConfigSourceDescription::Custom("test".to_string());
+            ConfigObject::new(Box::new(value), desc)
+        };
+        let layers = Layers::from_iter([
+            layer(serde_json::json!({ "server": { "port": 8080 } })),
+            // Matches `server`, but not `server.port`
+            layer(serde_json::json!({ "server": { "host": "localhost" }, "port": 1 })),
+        ]);
+
+        let port = layers.get("server.port").unwrap().unwrap();
+        assert_eq!(port.as_u16(), Some(8080));
+        let view = layers.get_view("server.port").unwrap().unwrap();
+        assert_eq!(view.value().as_u16(), Some(8080));
+        let port = layers
+            .get_as::<_, serde_json::Value>("server.port")
+            .unwrap();
+        assert_eq!(port, Some(&serde_json::json!(8080)));
+    }
+}
@@ -6,6 +6,8 @@ mod builder;
 #[allow(clippy::module_inception)]
 mod config;
 mod construct;
+mod decrypt;
+mod encoded;
 mod error;
 mod layers;
 
@@ -16,6 +18,10 @@ pub use crate::config::async_config::*;
 pub use crate::config::builder::*;
 pub use crate::config::config::*;
 pub use crate::config::construct::*;
+pub use crate::config::decrypt::ValueCipher;
+#[cfg(feature = "base64")]
+pub use crate::config::encoded::Base64;
+pub use crate::config::encoded::Hex;
 pub use crate::config::error::*;
 pub use crate::config::layers::Layers;
 
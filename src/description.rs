@@ -6,5 +6,23 @@ pub enum ConfigSourceDescription {
     Overwrite,
     Path(std::path::PathBuf),
     Uri(url::Url),
+    Command(String),
+    Stdin,
+    Git {
+        repository: std::path::PathBuf,
+        reference: String,
+        commit: String,
+        paths: Vec<std::path::PathBuf>,
+    },
+    Table {
+        path: std::path::PathBuf,
+        table: String,
+    },
+    /// A stale configuration from the cache file at `path`, used because loading the source
+    /// failed with `error`
+    Cached {
+        path: std::path::PathBuf,
+        error: String,
+    },
     Custom(String),
 }
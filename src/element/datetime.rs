@@ -0,0 +1,294 @@
+use super::ConfigElementType;
+
+/// A date and/or time value, as defined by RFC 3339 and TOML
+///
+/// Depending on which fields are set, this is one of
+///
+/// * an offset datetime (`1979-05-27T07:32:00-08:00`): date, time and offset
+/// * a local datetime (`1979-05-27T07:32:00`): date and time
+/// * a local date (`1979-05-27`): date only
+/// * a local time (`07:32:00`): time only
+///
+/// Formats without native datetimes can provide them as strings, which are parsed with
+/// [Datetime::from_str](std::str::FromStr::from_str) when constructing a value with
+/// [FromConfigElement](crate::FromConfigElement).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Datetime {
+    pub date: Option<Date>,
+    pub time: Option<Time>,
+    pub offset: Option<Offset>,
+}
+
+/// A calendar date
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    pub year: u16,
+
+    /// The month, from 1 to 12
+    pub month: u8,
+
+    /// The day of the month, from 1 to 31
+    pub day: u8,
+}
+
+/// A time of day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+
+    /// The second, from 0 to 60 (for leap seconds)
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// The offset of a datetime from UTC
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Offset {
+    /// UTC, written as `Z`
+    Z,
+
+    /// An offset of `minutes` from UTC, written as `+HH:MM` or `-HH:MM`
+    Custom { minutes: i16 },
+}
+
+impl Offset {
+    /// The offset from UTC in minutes
+    pub fn minutes(&self) -> i16 {
+        match self {
+            Offset::Z => 0,
+            Offset::Custom { minutes } => *minutes,
+        }
+    }
+}
+
+impl Datetime {
+    /// The [ConfigElementType] of a value holding this datetime
+    pub(crate) fn element_type(&self) -> ConfigElementType {
+        match (self.date, self.time, self.offset) {
+            (Some(_), Some(_), Some(_)) => ConfigElementType::OffsetDatetime,
+            (Some(_), Some(_), None) => ConfigElementType::LocalDatetime,
+            (Some(_), None, _) => ConfigElementType::LocalDate,
+            (None, _, _) => ConfigElementType::LocalTime,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to parse '{0}' as RFC 3339 date and/or time")]
+pub struct DatetimeParseError(String);
+
+impl std::str::FromStr for Datetime {
+    type Err = DatetimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| DatetimeParseError(s.to_string()))
+    }
+}
+
+fn parse(s: &str) -> Option<Datetime> {
+    let mut parser = Parser(s.as_bytes());
+
+    if s.as_bytes().get(2) == Some(&b':') {
+        let time = parser.time()?;
+        return parser.0.is_empty().then_some(Datetime {
+            date: None,
+            time: Some(time),
+            offset: None,
+        });
+    }
+
+    let date = parser.date()?;
+    if parser.0.is_empty() {
+        return Some(Datetime {
+            date: Some(date),
+            time: None,
+            offset: None,
+        });
+    }
+
+    if !parser.eat(|b| std::matches!(b, b'T' | b't' | b' ')) {
+        return None;
+    }
+    let time = parser.time()?;
+    let offset = parser.offset()?;
+
+    parser.0.is_empty().then_some(Datetime {
+        date: Some(date),
+        time: Some(time),
+        offset,
+    })
+}
+
+fn is_leap_year(year: u16) -> bool {
+    std::matches!((year % 4, year % 100, year % 400), (0, 1.., _) | (_, _, 0))
+}
+
+struct Parser<'a>(&'a [u8]);
+
+impl Parser<'_> {
+    fn eat(&mut self, accept: impl Fn(u8) -> bool) -> bool {
+        match self.0.split_first() {
+            Some((byte, rest)) if accept(*byte) => {
+                self.0 = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn digits(&mut self, count: usize) -> Option<u32> {
+        let digits = self.0.get(..count)?;
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        self.0 = &self.0[count..];
+        Some(digits.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+    }
+
+    fn date(&mut self) -> Option<Date> {
+        let year = self.digits(4)? as u16;
+        self.eat(|b| b == b'-').then_some(())?;
+        let month = self.digits(2)? as u8;
+        self.eat(|b| b == b'-').then_some(())?;
+        let day = self.digits(2)? as u8;
+
+        let days_in_month = match month {
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Date { year, month, day })
+    }
+
+    fn time(&mut self) -> Option<Time> {
+        let hour = self.digits(2)? as u8;
+        self.eat(|b| b == b':').then_some(())?;
+        let minute = self.digits(2)? as u8;
+        self.eat(|b| b == b':').then_some(())?;
+        let second = self.digits(2)? as u8;
+
+        let mut nanosecond = 0;
+        if self.eat(|b| b == b'.') {
+            let count = self.0.iter().take_while(|b| b.is_ascii_digit()).count();
+            if count == 0 {
+                return None;
+            }
+            // Digits beyond nanosecond precision are truncated
+            let precision = count.min(9);
+            nanosecond = self.digits(precision)? * 10u32.pow((9 - precision) as u32);
+            self.0 = &self.0[count - precision..];
+        }
+
+        (hour < 24 && minute < 60 && second <= 60).then_some(Time {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        })
+    }
+
+    /// Parse an optional offset, returning `None` if it is malformed
+    fn offset(&mut self) -> Option<Option<Offset>> {
+        if self.eat(|b| std::matches!(b, b'Z' | b'z')) {
+            return Some(Some(Offset::Z));
+        }
+
+        let sign = match self.0.first() {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Some(None),
+        };
+        self.0 = &self.0[1..];
+
+        let hours = self.digits(2)? as i16;
+        self.eat(|b| b == b':').then_some(())?;
+        let minutes = self.digits(2)? as i16;
+
+        (hours < 24 && minutes < 60).then_some(Some(Offset::Custom {
+            minutes: sign * (hours * 60 + minutes),
+        }))
+    }
+}
+
+impl std::fmt::Display for Datetime {
+    /// Formats the datetime as RFC 3339 (or the date or time part of it)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(date) = &self.date {
+            write!(f, "{:04}-{:02}-{:02}", date.year, date.month, date.day)?;
+        }
+        if let Some(time) = &self.time {
+            if self.date.is_some() {
+                write!(f, "T")?;
+            }
+            write!(f, "{:02}:{:02}:{:02}", time.hour, time.minute, time.second)?;
+            if time.nanosecond != 0 {
+                let fraction = format!("{:09}", time.nanosecond);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+        }
+        match self.offset {
+            Some(Offset::Z) => write!(f, "Z"),
+            Some(Offset::Custom { minutes }) => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let dt: Datetime = "1979-05-27T07:32:00.999999-07:30".parse().unwrap();
+        assert_eq!(
+            dt.date,
+            Some(Date {
+                year: 1979,
+                month: 5,
+                day: 27
+            })
+        );
+        assert_eq!(dt.time.unwrap().nanosecond, 999_999_000);
+        assert_eq!(dt.offset, Some(Offset::Custom { minutes: -450 }));
+        assert_eq!(dt.to_string(), "1979-05-27T07:32:00.999999-07:30");
+
+        let dt: Datetime = "1979-05-27 07:32:00".parse().unwrap();
+        assert_eq!(dt.offset, None);
+        assert_eq!(dt.to_string(), "1979-05-27T07:32:00");
+
+        let dt: Datetime = "2000-02-29".parse().unwrap();
+        assert!(dt.time.is_none());
+
+        let dt: Datetime = "00:00:60.5".parse().unwrap();
+        assert_eq!(dt.to_string(), "00:00:60.5");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "",
+            "1979",
+            "1979-5-27",
+            "1900-02-29",
+            "1979-13-01",
+            "1979-05-27T",
+            "1979-05-27T24:00:00",
+            "1979-05-27T07:32:00+25:00",
+            "1979-05-27T07:32:00.Z",
+            "1979-05-27T07:32:00Z ",
+            "07:32:00Z",
+        ] {
+            assert!(s.parse::<Datetime>().is_err(), "Parsed '{}'", s);
+        }
+    }
+}
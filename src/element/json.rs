@@ -2,7 +2,9 @@ use crate::element::ConfigElement;
 
 use serde_json::Value;
 
-use super::{ConfigElementListType, ConfigElementMapType};
+use super::{ConfigElementListType, ConfigElementMapType, Number};
+use crate::span::Lines;
+use crate::span::Spans;
 
 impl ConfigElement for Value {
     fn is_null(&self) -> bool {
@@ -13,62 +15,6 @@ impl ConfigElement for Value {
         std::matches!(self, Value::Bool(_))
     }
 
-    fn is_i8(&self) -> bool {
-        self.as_i64().filter(|i| *i < (i8::MAX as i64)).is_some()
-    }
-
-    fn is_i16(&self) -> bool {
-        self.as_i64().filter(|i| *i < (i16::MAX as i64)).is_some()
-    }
-
-    fn is_i32(&self) -> bool {
-        self.as_i64().filter(|i| *i < (i32::MAX as i64)).is_some()
-    }
-
-    fn is_i64(&self) -> bool {
-        self.as_i64().filter(|i| *i < i64::MAX).is_some()
-    }
-
-    fn is_u8(&self) -> bool {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u8::MAX as i64))
-            .is_some()
-    }
-
-    fn is_u16(&self) -> bool {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u16::MAX as i64))
-            .is_some()
-    }
-
-    fn is_u32(&self) -> bool {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u32::MAX as i64))
-            .is_some()
-    }
-
-    fn is_u64(&self) -> bool {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u64::MAX as i64))
-            .is_some()
-    }
-
-    fn is_f32(&self) -> bool {
-        self.is_f64()
-            && self
-                .as_f64()
-                .filter(|f| *f < (f32::MAX as f64) && *f > (f32::MIN as f64))
-                .is_some()
-    }
-
-    fn is_f64(&self) -> bool {
-        self.is_f64()
-    }
-
     fn is_str(&self) -> bool {
         std::matches!(self, Value::String(_))
     }
@@ -85,64 +31,15 @@ impl ConfigElement for Value {
         self.as_bool()
     }
 
-    fn as_i8(&self) -> Option<i8> {
-        self.as_i64()
-            .filter(|i| (*i < (i8::MAX as i64)))
-            .map(|i| i as i8)
-    }
-
-    fn as_i16(&self) -> Option<i16> {
-        self.as_i64()
-            .filter(|i| (*i < (i16::MAX as i64)))
-            .map(|i| i as i16)
-    }
-
-    fn as_i32(&self) -> Option<i32> {
-        self.as_i64()
-            .filter(|i| (*i < (i32::MAX as i64)))
-            .map(|i| i as i32)
-    }
-
-    fn as_i64(&self) -> Option<i64> {
-        self.as_i64()
-    }
-
-    fn as_u8(&self) -> Option<u8> {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u8::MAX as i64)))
-            .map(|i| i as u8)
-    }
-
-    fn as_u16(&self) -> Option<u16> {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u16::MAX as i64)))
-            .map(|i| i as u16)
-    }
-
-    fn as_u32(&self) -> Option<u32> {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u32::MAX as i64)))
-            .map(|i| i as u32)
-    }
-
-    fn as_u64(&self) -> Option<u64> {
-        self.as_i64()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u64::MAX as i64)))
-            .map(|i| i as u64)
-    }
-
-    fn as_f32(&self) -> Option<f32> {
-        self.as_f64()
-            .filter(|f| *f < (f32::MAX as f64) && *f > (f32::MIN as f64))
-            .map(|f| f as f32)
-    }
-
-    fn as_f64(&self) -> Option<f64> {
-        self.as_f64()
+    fn as_number(&self) -> Option<Number> {
+        let number = self.as_number()?;
+        if let Some(u) = number.as_u64() {
+            Some(Number::from(u))
+        } else if let Some(i) = number.as_i64() {
+            Some(Number::from(i))
+        } else {
+            number.as_f64().map(Number::from)
+        }
     }
 
     fn as_str(&self) -> Option<&str> {
@@ -163,13 +60,150 @@ impl ConfigElementMapType for serde_json::Map<String, serde_json::Value> {
         serde_json::Map::get(self, key).map(|t| t as &dyn ConfigElement)
     }
 
-    fn keys(&self) -> Vec<String> {
-        serde_json::Map::keys(self).map(String::to_owned).collect()
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        Box::new(serde_json::Map::iter(self).map(|(k, t)| (k.as_str(), t as &dyn ConfigElement)))
+    }
+}
+
+impl From<Value> for super::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => super::Value::Null,
+            Value::Bool(b) => super::Value::Bool(b),
+            Value::Number(_) => super::Value::Number(ConfigElement::as_number(&value).unwrap()),
+            Value::String(s) => super::Value::Str(s),
+            Value::Array(a) => super::Value::List(a.into_iter().map(Self::from).collect()),
+            Value::Object(o) => super::Value::Map(
+                o.into_iter()
+                    .map(|(key, value)| (key, Self::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Record the spans of the values of a JSON document
+///
+/// serde_json does not report spans, so the document is scanned again. It must be valid JSON.
+pub(crate) fn spans(document: &str) -> Spans {
+    let mut spans = Spans::new();
+    let mut scanner = Scanner {
+        document,
+        lines: Lines::new(document),
+        bytes: document.as_bytes(),
+        pos: 0,
+    };
+    scanner.value(&mut spans);
+    spans
+}
+
+struct Scanner<'a> {
+    document: &'a str,
+    lines: Lines<'a>,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while std::matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip whitespace and `byte`, if it is the next character
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Scan the value at the current position, recording its span and the spans of its children
+    fn value(&mut self, spans: &mut Spans) {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                while !self.eat(b'}') && self.peek().is_some() {
+                    self.eat(b',');
+                    self.skip_whitespace();
+                    let key = self.string();
+                    self.eat(b':');
+                    let key: String = serde_json::from_str(key).unwrap_or_default();
+                    self.value(spans.key(&key));
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                while !self.eat(b']') && self.peek().is_some() {
+                    self.eat(b',');
+                    self.value(spans.index(index));
+                    index += 1;
+                }
+            }
+            Some(b'"') => {
+                self.string();
+            }
+            _ => {
+                while !std::matches!(
+                    self.peek(),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+            }
+        }
+
+        spans.set(self.lines.span(start..self.pos));
+    }
+
+    /// Scan a string at the current position, returning it including the quotes
+    fn string(&mut self) -> &'a str {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'\\' => self.pos += 1,
+                b'"' => break,
+                _ => {}
+            }
+        }
+        &self.document[start..self.pos.min(self.bytes.len())]
     }
+}
 
-    fn values(&self) -> Vec<&dyn ConfigElement> {
-        serde_json::Map::values(self)
-            .map(|t| t as &dyn ConfigElement)
-            .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans() {
+        let document = r#"{
+    "name": "app",
+    "servers": [ { "host": "a\"b" }, 2 ],
+    "empty": {}
+}"#;
+        let spans = spans(document);
+        let get = |path: &str| {
+            let accessor = crate::ParsableAccessor::parse(&path).unwrap();
+            *spans.get(accessor.remaining()).unwrap()
+        };
+
+        assert_eq!(&document[get("name").range()], r#""app""#);
+        assert_eq!((get("name").line, get("name").column), (2, 13));
+        assert_eq!(&document[get("servers.0.host").range()], r#""a\"b""#);
+        assert_eq!(&document[get("servers.1").range()], "2");
+        assert_eq!(&document[get("empty").range()], "{}");
     }
 }
@@ -1,8 +1,24 @@
 use crate::{
     accessor::{AccessType, Accessor},
+    description::ConfigSourceDescription,
     object::ConfigObjectAccessError,
 };
 
+mod datetime;
+pub use self::datetime::{Date, Datetime, DatetimeParseError, Offset, Time};
+
+mod number;
+pub use self::number::{Number, NumberParseError};
+
+mod shared;
+pub(crate) use self::shared::Shared;
+
+mod value;
+pub use self::value::Value;
+
+mod walk;
+pub use self::walk::Walk;
+
 pub trait ConfigElementListType {
     fn len(&self) -> usize;
 
@@ -16,6 +32,13 @@ pub trait ConfigElementListType {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Iterate over the elements of the list, in order
+    ///
+    /// Auto-implemented using Self::len() and Self::at_index().
+    fn iter(&self) -> Box<dyn Iterator<Item = &dyn ConfigElement> + '_> {
+        Box::new((0..self.len()).filter_map(move |index| self.at_index(index)))
+    }
 }
 
 impl<T> ConfigElementListType for Vec<T>
@@ -29,6 +52,10 @@ where
     fn at_index(&self, index: usize) -> Option<&dyn ConfigElement> {
         self.get(index).map(|t| t as &dyn ConfigElement)
     }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &dyn ConfigElement> + '_> {
+        Box::new(self.as_slice().iter().map(|t| t as &dyn ConfigElement))
+    }
 }
 
 pub trait ConfigElementMapType {
@@ -38,10 +65,82 @@ pub trait ConfigElementMapType {
         self.get(key).is_some()
     }
 
-    fn keys(&self) -> Vec<String>;
-    fn values(&self) -> Vec<&dyn ConfigElement>;
+    /// Iterate over the keys and values of the map
+    ///
+    /// # Ordering
+    ///
+    /// The entries are returned in the order of the underlying map, which is the same on every
+    /// call. Where the format preserves the order of the document, this is the insertion order:
+    ///
+    /// * JSON and TOML maps are sorted by key, unless the `preserve_order` feature of `serde_json`
+    ///   or `toml` is enabled, in which case they are in document order
+    /// * [Value](crate::Value) maps and the maps of key-value sources (environment, SQLite, ...)
+    ///   are sorted by key
+    /// * Maps merged from several documents are in the order of the first occurrence of each key
+    ///
+    /// # Breaking change
+    ///
+    /// This method replaces `keys` and `values` as the required method of this trait. Maps that
+    /// only implemented those must implement `entries` instead; `keys` and `values` are now
+    /// provided on top of it.
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_>;
+
+    /// Iterate over the keys of the map, in the order of [ConfigElementMapType::entries]
+    ///
+    /// Auto-implemented using Self::entries().
+    fn keys_iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.entries().map(|(key, _)| key))
+    }
+
+    /// Get the keys of the map, in the order of [ConfigElementMapType::entries]
+    ///
+    /// This allocates every key, prefer [ConfigElementMapType::keys_iter].
+    fn keys(&self) -> Vec<String> {
+        self.keys_iter().map(String::from).collect()
+    }
+
+    /// Get the values of the map, in the order of [ConfigElementMapType::entries]
+    fn values(&self) -> Vec<&dyn ConfigElement> {
+        self.entries().map(|(_, value)| value).collect()
+    }
+}
+
+/// A format-specific value that does not fit into the types of [ConfigElementType]
+///
+/// Examples are tagged values, RON enums or YAML tags. A [ConfigElement] returns these from
+/// [ConfigElement::as_custom]. The element is reported as [ConfigElementType::Custom] with the
+/// name of [ConfigElementCustomType::type_name], for example in error messages.
+///
+/// The payload is accessed by downcasting to the concrete type:
+///
+/// ```rust
+/// # use config_rs_ng::ConfigElementCustomType;
+/// #[derive(Debug)]
+/// struct Tagged {
+///     tag: String,
+///     value: String,
+/// }
+///
+/// impl ConfigElementCustomType for Tagged {
+///     fn type_name(&self) -> &'static str {
+///         "tagged"
+///     }
+/// }
+///
+/// let custom: Box<dyn ConfigElementCustomType> = Box::new(Tagged {
+///     tag: "!secret".to_string(),
+///     value: "hunter2".to_string(),
+/// });
+/// let tagged = custom.downcast_ref::<Tagged>().unwrap();
+/// assert_eq!(tagged.tag, "!secret");
+/// ```
+pub trait ConfigElementCustomType: std::fmt::Debug + downcast_rs::DowncastSync {
+    /// The name of the type
+    fn type_name(&self) -> &'static str;
 }
 
+downcast_rs::impl_downcast!(sync ConfigElementCustomType);
+
 /// The ConfigElement trait that makes a type usable with this crate
 ///
 /// # Warning
@@ -53,7 +152,9 @@ pub trait ConfigElementMapType {
 ///
 /// # Implementing
 ///
-/// A user of this library may only implement the `as_*` functions of this trait. The `is_*`
+/// A user of this library may only implement the `as_*` functions of this trait. Numbers are
+/// provided by implementing `as_number()`, from which the sized `as_i8()`, `as_u64()`, ...
+/// functions are derived with exact range checks. The `is_*`
 /// functions are auto-implemented by calling the corrosponding `as_*` function and checking the
 /// returned `Option` with `Option::is_some()`. If it is the case that that behaviour is
 /// inefficient for a implementation of this trait, the user is welcome to implement the `is_*`
@@ -66,20 +167,105 @@ pub trait ConfigElementMapType {
 /// traverse the object tree when accessing values in a configuration.
 pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
     fn as_bool(&self) -> Option<bool>;
-    fn as_i8(&self) -> Option<i8>;
-    fn as_i16(&self) -> Option<i16>;
-    fn as_i32(&self) -> Option<i32>;
-    fn as_i64(&self) -> Option<i64>;
-    fn as_u8(&self) -> Option<u8>;
-    fn as_u16(&self) -> Option<u16>;
-    fn as_u32(&self) -> Option<u32>;
-    fn as_u64(&self) -> Option<u64>;
-    fn as_f32(&self) -> Option<f32>;
-    fn as_f64(&self) -> Option<f64>;
+
+    /// Get the value as a number in its canonical representation
+    ///
+    /// The sized number accessors are implemented using this function.
+    ///
+    /// Returns `None` by default.
+    fn as_number(&self) -> Option<Number> {
+        None
+    }
+
+    fn as_i8(&self) -> Option<i8> {
+        self.as_number()?.as_integer()
+    }
+    fn as_i16(&self) -> Option<i16> {
+        self.as_number()?.as_integer()
+    }
+    fn as_i32(&self) -> Option<i32> {
+        self.as_number()?.as_integer()
+    }
+    fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_integer()
+    }
+
+    /// Get the value as `i128`
+    ///
+    /// Falls back to `as_i64()` and `as_u64()` for implementations that do not implement
+    /// `as_number()`.
+    fn as_i128(&self) -> Option<i128> {
+        match self.as_number() {
+            Some(number) => number.as_integer(),
+            None => self
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| self.as_u64().map(i128::from)),
+        }
+    }
+    fn as_u8(&self) -> Option<u8> {
+        self.as_number()?.as_integer()
+    }
+    fn as_u16(&self) -> Option<u16> {
+        self.as_number()?.as_integer()
+    }
+    fn as_u32(&self) -> Option<u32> {
+        self.as_number()?.as_integer()
+    }
+    fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_integer()
+    }
+
+    /// Get the value as `u128`
+    ///
+    /// Falls back to `as_u64()` for implementations that do not implement `as_number()`.
+    fn as_u128(&self) -> Option<u128> {
+        match self.as_number() {
+            Some(number) => number.as_integer(),
+            None => self.as_u64().map(u128::from),
+        }
+    }
+    fn as_f32(&self) -> Option<f32> {
+        self.as_number()?.as_f32()
+    }
+    fn as_f64(&self) -> Option<f64> {
+        self.as_number()?.as_f64()
+    }
     fn as_str(&self) -> Option<&str>;
     fn as_list(&self) -> Option<&dyn ConfigElementListType>;
     fn as_map(&self) -> Option<&dyn ConfigElementMapType>;
 
+    /// Get the value as a format-specific type
+    ///
+    /// This is only used if none of the other `as_*` functions return `Some(_)`. A value that can
+    /// also be represented as one of the other types (e.g. a tagged map) should implement those
+    /// as well, so it can be accessed like any other value.
+    ///
+    /// Returns `None` by default.
+    fn as_custom(&self) -> Option<&dyn ConfigElementCustomType> {
+        None
+    }
+
+    /// Get the value as native binary data
+    ///
+    /// Only formats with a binary type should implement this. Encoded strings (e.g. base64) are
+    /// not decoded here, see [Base64](crate::Base64) and [Hex](crate::Hex) for that.
+    ///
+    /// Returns `None` by default.
+    fn as_bytes(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// Get the value as a native date and/or time
+    ///
+    /// Only formats with native datetimes (like TOML) should implement this. Strings are not
+    /// parsed here, so they keep their [ConfigElementType::Str] type.
+    ///
+    /// Returns `None` by default.
+    fn as_datetime(&self) -> Option<Datetime> {
+        None
+    }
+
     fn is_null(&self) -> bool;
     fn is_bool(&self) -> bool {
         self.as_bool().is_some()
@@ -96,6 +282,9 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
     fn is_i64(&self) -> bool {
         self.as_i64().is_some()
     }
+    fn is_i128(&self) -> bool {
+        self.as_i128().is_some()
+    }
     fn is_u8(&self) -> bool {
         self.as_u8().is_some()
     }
@@ -108,6 +297,9 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
     fn is_u64(&self) -> bool {
         self.as_u64().is_some()
     }
+    fn is_u128(&self) -> bool {
+        self.as_u128().is_some()
+    }
     fn is_f32(&self) -> bool {
         self.as_f32().is_some()
     }
@@ -123,6 +315,15 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
     fn is_map(&self) -> bool {
         self.as_map().is_some()
     }
+    fn is_custom(&self) -> bool {
+        self.as_custom().is_some()
+    }
+    fn is_bytes(&self) -> bool {
+        self.as_bytes().is_some()
+    }
+    fn is_datetime(&self) -> bool {
+        self.as_datetime().is_some()
+    }
 
     /// Internal helper function
     ///
@@ -158,6 +359,13 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
         if self.is_u64() {
             return ConfigElementType::U64;
         }
+        // Only integers outside of the range of i64 and u64 are reported as 128-bit
+        if self.is_i128() {
+            return ConfigElementType::I128;
+        }
+        if self.is_u128() {
+            return ConfigElementType::U128;
+        }
         if self.is_f32() {
             return ConfigElementType::F32;
         }
@@ -167,16 +375,38 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
         if self.is_str() {
             return ConfigElementType::Str;
         }
+        if self.is_bytes() {
+            return ConfigElementType::Bytes;
+        }
         if self.is_list() {
             return ConfigElementType::List;
         }
         if self.is_map() {
             return ConfigElementType::Map;
         }
+        if let Some(datetime) = self.as_datetime() {
+            return datetime.element_type();
+        }
+        if let Some(custom) = self.as_custom() {
+            return ConfigElementType::Custom(custom.type_name());
+        }
 
         unreachable!()
     }
 
+    /// Describe where this element was defined, if that differs from the description of the
+    /// [ConfigObject](crate::ConfigObject) that holds it
+    ///
+    /// Sources that assemble a configuration tree from multiple places (for example the keys of a
+    /// key-value store) can use this to record the origin of each individual value.
+    /// [ConfigView::description](crate::ConfigView::description) prefers this description over the
+    /// one of the [ConfigObject](crate::ConfigObject).
+    ///
+    /// Returns `None` by default.
+    fn source_description(&self) -> Option<&ConfigSourceDescription> {
+        None
+    }
+
     fn access(
         &self,
         accessor: &mut Accessor,
@@ -200,6 +430,9 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
             (Some(AccessType::Key(k)), ConfigElementType::I64) => {
                 Err(ConfigObjectAccessError::AccessWithKeyOnI64(k.to_string()))
             }
+            (Some(AccessType::Key(k)), ConfigElementType::I128) => {
+                Err(ConfigObjectAccessError::AccessWithKeyOnI128(k.to_string()))
+            }
             (Some(AccessType::Key(k)), ConfigElementType::U8) => {
                 Err(ConfigObjectAccessError::AccessWithKeyOnU8(k.to_string()))
             }
@@ -212,6 +445,9 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
             (Some(AccessType::Key(k)), ConfigElementType::U64) => {
                 Err(ConfigObjectAccessError::AccessWithKeyOnU64(k.to_string()))
             }
+            (Some(AccessType::Key(k)), ConfigElementType::U128) => {
+                Err(ConfigObjectAccessError::AccessWithKeyOnU128(k.to_string()))
+            }
             (Some(AccessType::Key(k)), ConfigElementType::F32) => {
                 Err(ConfigObjectAccessError::AccessWithKeyOnF32(k.to_string()))
             }
@@ -221,9 +457,24 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
             (Some(AccessType::Key(k)), ConfigElementType::Str) => {
                 Err(ConfigObjectAccessError::AccessWithKeyOnStr(k.to_string()))
             }
+            (Some(AccessType::Key(k)), ConfigElementType::Bytes) => {
+                Err(ConfigObjectAccessError::AccessWithKeyOnBytes(k.to_string()))
+            }
             (Some(AccessType::Key(k)), ConfigElementType::List) => {
                 Err(ConfigObjectAccessError::AccessWithKeyOnList(k.to_string()))
             }
+            (
+                Some(AccessType::Key(k)),
+                ConfigElementType::OffsetDatetime
+                | ConfigElementType::LocalDatetime
+                | ConfigElementType::LocalDate
+                | ConfigElementType::LocalTime,
+            ) => Err(ConfigObjectAccessError::AccessWithKeyOnDatetime(
+                k.to_string(),
+            )),
+            (Some(AccessType::Key(k)), ConfigElementType::Custom(name)) => Err(
+                ConfigObjectAccessError::AccessWithKeyOnCustom(name, k.to_string()),
+            ),
             (Some(AccessType::Key(k)), ConfigElementType::Map) => {
                 if let Some(hm) = self.as_map() {
                     if let Some(value) = hm.get(k.as_str()) {
@@ -259,6 +510,9 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
             (Some(AccessType::Index(u)), ConfigElementType::I64) => {
                 Err(ConfigObjectAccessError::AccessWithIndexOnI64(*u))
             }
+            (Some(AccessType::Index(u)), ConfigElementType::I128) => {
+                Err(ConfigObjectAccessError::AccessWithIndexOnI128(*u))
+            }
             (Some(AccessType::Index(u)), ConfigElementType::U8) => {
                 Err(ConfigObjectAccessError::AccessWithIndexOnU8(*u))
             }
@@ -271,6 +525,9 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
             (Some(AccessType::Index(u)), ConfigElementType::U64) => {
                 Err(ConfigObjectAccessError::AccessWithIndexOnU64(*u))
             }
+            (Some(AccessType::Index(u)), ConfigElementType::U128) => {
+                Err(ConfigObjectAccessError::AccessWithIndexOnU128(*u))
+            }
             (Some(AccessType::Index(u)), ConfigElementType::F32) => {
                 Err(ConfigObjectAccessError::AccessWithIndexOnF32(*u))
             }
@@ -280,9 +537,22 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
             (Some(AccessType::Index(u)), ConfigElementType::Str) => {
                 Err(ConfigObjectAccessError::AccessWithIndexOnStr(*u))
             }
+            (Some(AccessType::Index(u)), ConfigElementType::Bytes) => {
+                Err(ConfigObjectAccessError::AccessWithIndexOnBytes(*u))
+            }
             (Some(AccessType::Index(u)), ConfigElementType::Map) => {
                 Err(ConfigObjectAccessError::AccessWithIndexOnMap(*u))
             }
+            (
+                Some(AccessType::Index(u)),
+                ConfigElementType::OffsetDatetime
+                | ConfigElementType::LocalDatetime
+                | ConfigElementType::LocalDate
+                | ConfigElementType::LocalTime,
+            ) => Err(ConfigObjectAccessError::AccessWithIndexOnDatetime(*u)),
+            (Some(AccessType::Index(u)), ConfigElementType::Custom(name)) => {
+                Err(ConfigObjectAccessError::AccessWithIndexOnCustom(name, *u))
+            }
             (Some(AccessType::Index(u)), ConfigElementType::List) => {
                 if let Some(list) = self.as_list() {
                     if let Some(value) = list.at_index(*u) {
@@ -305,6 +575,7 @@ pub trait ConfigElement: std::fmt::Debug + downcast_rs::DowncastSync {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConfigElementType {
     Null,
     Bool,
@@ -312,15 +583,25 @@ pub enum ConfigElementType {
     I16,
     I32,
     I64,
+    I128,
     U8,
     U16,
     U32,
     U64,
+    U128,
     F32,
     F64,
     Str,
+    Bytes,
     List,
     Map,
+    OffsetDatetime,
+    LocalDatetime,
+    LocalDate,
+    LocalTime,
+
+    /// A [ConfigElementCustomType] with the given type name
+    Custom(&'static str),
 }
 
 impl ConfigElementType {
@@ -332,15 +613,23 @@ impl ConfigElementType {
             ConfigElementType::I16 => "i16",
             ConfigElementType::I32 => "i32",
             ConfigElementType::I64 => "i64",
+            ConfigElementType::I128 => "i128",
             ConfigElementType::U8 => "u8",
             ConfigElementType::U16 => "u16",
             ConfigElementType::U32 => "u32",
             ConfigElementType::U64 => "u64",
+            ConfigElementType::U128 => "u128",
             ConfigElementType::F32 => "f32",
             ConfigElementType::F64 => "f64",
             ConfigElementType::Str => "str",
+            ConfigElementType::Bytes => "bytes",
             ConfigElementType::List => "list",
             ConfigElementType::Map => "map",
+            ConfigElementType::OffsetDatetime => "offset datetime",
+            ConfigElementType::LocalDatetime => "local datetime",
+            ConfigElementType::LocalDate => "local date",
+            ConfigElementType::LocalTime => "local time",
+            ConfigElementType::Custom(name) => name,
         }
     }
 }
@@ -421,4 +710,25 @@ mod tests {
         assert!(r.is_str());
         assert_eq!(r.as_str().unwrap(), "a");
     }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_iterators() {
+        use super::ConfigElement;
+
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{ "b": [1, 2, 3], "a": { "c": true } }"#).unwrap();
+        let map = ConfigElement::as_map(&json).unwrap();
+
+        let keys: Vec<&str> = map.keys_iter().collect();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(map.keys(), ["a", "b"]);
+
+        let (key, list) = map.entries().nth(1).unwrap();
+        assert_eq!(key, "b");
+        let list = list.as_list().unwrap();
+        let numbers: Vec<u8> = list.iter().filter_map(|e| e.as_u8()).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        assert_eq!(list.iter().count(), list.len());
+    }
 }
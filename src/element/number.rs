@@ -0,0 +1,187 @@
+/// A number in its canonical representation
+///
+/// Format backends convert their numbers into this type in
+/// [ConfigElement::as_number](crate::ConfigElement::as_number). The sized accessors
+/// ([ConfigElement::as_u8](crate::ConfigElement::as_u8), ...) are derived from it, so that every
+/// backend applies the same range checks: an integer is returned if it fits into the requested type
+/// exactly, a float only if it is in the range of the requested type.
+///
+/// Unlike `f64`, numbers implement [Eq] and [Hash](std::hash::Hash): all NaNs are equal to each
+/// other. Integers and floats are never equal, even if they have the same value.
+#[derive(Clone, Copy, Debug)]
+pub enum Number {
+    /// A non-negative integer
+    PosInt(u128),
+
+    /// A negative integer
+    NegInt(i128),
+
+    Float(f64),
+}
+
+impl Number {
+    /// Get the number as integer type `T`, if it is an integer in the range of `T`
+    pub fn as_integer<T>(&self) -> Option<T>
+    where
+        T: TryFrom<u128> + TryFrom<i128>,
+    {
+        match *self {
+            Number::PosInt(u) => T::try_from(u).ok(),
+            Number::NegInt(i) => T::try_from(i).ok(),
+            Number::Float(_) => None,
+        }
+    }
+
+    /// Get the number as `f64`
+    ///
+    /// Integers are only returned if they can be represented as `f64` exactly.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            // Casting saturates, so 2^128 would compare equal to u128::MAX
+            Number::PosInt(u) => Some(u as f64).filter(|f| *f < 2f64.powi(128) && *f as u128 == u),
+            Number::NegInt(i) => Some(i as f64).filter(|f| *f as i128 == i),
+            Number::Float(f) => Some(f),
+        }
+    }
+
+    /// Get the number as `f32`
+    ///
+    /// Integers are only returned if they can be represented as `f32` exactly, floats only if they
+    /// are in the range of `f32` (or not finite).
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            Number::PosInt(u) => Some(u as f32).filter(|f| f.is_finite() && *f as u128 == u),
+            Number::NegInt(i) => Some(i as f32).filter(|f| *f as i128 == i),
+            Number::Float(f) => Some(f)
+                .filter(|f| !f.is_finite() || (f32::MIN as f64..=f32::MAX as f64).contains(f))
+                .map(|f| f as f32),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (Number::PosInt(a), Number::PosInt(b)) => a == b,
+            (Number::NegInt(a), Number::NegInt(b)) => a == b,
+            (Number::Float(a), Number::Float(b)) => a == b || (a.is_nan() && b.is_nan()),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Number {}
+
+impl std::hash::Hash for Number {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match *self {
+            Number::PosInt(u) => u.hash(state),
+            Number::NegInt(i) => i.hash(state),
+            Number::Float(f) if f.is_nan() => f64::NAN.to_bits().hash(state),
+            // 0.0 and -0.0 are equal, so they must hash equally
+            Number::Float(f) => (f + 0.0).to_bits().hash(state),
+        }
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Number {
+                fn from(u: $t) -> Self {
+                    Number::PosInt(u.into())
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Number {
+                fn from(i: $t) -> Self {
+                    match u128::try_from(i) {
+                        Ok(u) => Number::PosInt(u),
+                        Err(_) => Number::NegInt(i.into()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128);
+impl_from_signed!(i8, i16, i32, i64, i128);
+
+impl From<f32> for Number {
+    fn from(f: f32) -> Self {
+        Number::Float(f.into())
+    }
+}
+
+impl From<f64> for Number {
+    fn from(f: f64) -> Self {
+        Number::Float(f)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to parse '{0}' as number")]
+pub struct NumberParseError(String);
+
+impl std::str::FromStr for Number {
+    type Err = NumberParseError;
+
+    /// Parse an integer, or a float if the string is no integer
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(i) = s.parse::<i128>() {
+            Ok(Number::from(i))
+        } else if let Ok(u) = s.parse::<u128>() {
+            Ok(Number::from(u))
+        } else {
+            s.parse::<f64>()
+                .map(Number::from)
+                .map_err(|_| NumberParseError(s.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("255".parse::<Number>().unwrap(), Number::PosInt(255));
+        assert_eq!("-1".parse::<Number>().unwrap(), Number::NegInt(-1));
+        assert_eq!(
+            u128::MAX.to_string().parse::<Number>().unwrap(),
+            Number::PosInt(u128::MAX)
+        );
+        assert_eq!("1.5".parse::<Number>().unwrap(), Number::Float(1.5));
+        assert!("foo".parse::<Number>().is_err());
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(Number::from(1u64 << 53).as_f64(), Some(9007199254740992.0));
+        assert_eq!(Number::from((1u64 << 53) + 1).as_f64(), None);
+        assert_eq!(Number::from(-16777216).as_f32(), Some(-16777216.0));
+        assert_eq!(Number::from(16777217).as_f32(), None);
+        assert_eq!(Number::from(f32::MAX as f64).as_f32(), Some(f32::MAX));
+        assert_eq!(Number::from(f64::MAX).as_f32(), None);
+        assert_eq!(Number::from(f64::INFINITY).as_f32(), Some(f32::INFINITY));
+        assert_eq!(Number::from(u128::MAX).as_f64(), None);
+        assert_eq!(Number::from(u128::MAX).as_f32(), None);
+    }
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(Number::from(f64::NAN), Number::from(-f64::NAN));
+        assert_eq!(Number::from(0.0), Number::from(-0.0));
+        assert_ne!(Number::from(1), Number::from(1.0));
+        assert_ne!(Number::from(1), Number::from(-1));
+    }
+}
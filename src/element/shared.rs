@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use super::ConfigElement;
+use super::ConfigElementCustomType;
+use super::ConfigElementListType;
+use super::ConfigElementMapType;
+use super::Datetime;
+use super::Number;
+use crate::description::ConfigSourceDescription;
+
+/// An element that is shared unchanged with another configuration tree
+///
+/// Used by wrappers that rewrite parts of a layer (decrypted values, secret files) to hand out
+/// the original element of a layer without anything to rewrite.
+#[derive(Debug)]
+pub(crate) struct Shared(pub(crate) Arc<dyn ConfigElement>);
+
+macro_rules! delegate_to_shared {
+    ($($fun:ident -> $t:ty),* $(,)?) => {
+        $(
+            fn $fun(&self) -> Option<$t> {
+                self.0.$fun()
+            }
+        )*
+    };
+}
+
+impl ConfigElement for Shared {
+    delegate_to_shared! {
+        as_bool -> bool,
+        as_number -> Number,
+        as_str -> &str,
+        as_bytes -> &[u8],
+        as_list -> &dyn ConfigElementListType,
+        as_map -> &dyn ConfigElementMapType,
+        as_datetime -> Datetime,
+        as_custom -> &dyn ConfigElementCustomType,
+    }
+
+    fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+
+    fn source_description(&self) -> Option<&ConfigSourceDescription> {
+        self.0.source_description()
+    }
+}
@@ -2,7 +2,11 @@ use crate::element::ConfigElement;
 
 use toml::Value;
 
-use super::{ConfigElementListType, ConfigElementMapType};
+use super::{ConfigElementListType, ConfigElementMapType, Datetime, Number};
+use crate::comment::Comment;
+use crate::comment::Comments;
+use crate::span::Lines;
+use crate::span::Spans;
 
 impl ConfigElement for Value {
     fn is_null(&self) -> bool {
@@ -13,167 +17,391 @@ impl ConfigElement for Value {
         std::matches!(self, Value::Boolean(_))
     }
 
-    fn is_i8(&self) -> bool {
-        self.as_integer()
-            .filter(|i| *i < (i8::MAX as i64))
-            .is_some()
+    fn is_str(&self) -> bool {
+        std::matches!(self, Value::String(_))
     }
 
-    fn is_i16(&self) -> bool {
-        self.as_integer()
-            .filter(|i| *i < (i16::MAX as i64))
-            .is_some()
+    fn is_list(&self) -> bool {
+        std::matches!(self, Value::Array(_))
     }
 
-    fn is_i32(&self) -> bool {
-        self.as_integer()
-            .filter(|i| *i < (i32::MAX as i64))
-            .is_some()
+    fn is_map(&self) -> bool {
+        std::matches!(self, Value::Table(_))
     }
 
-    fn is_i64(&self) -> bool {
-        self.is_integer()
+    fn as_bool(&self) -> Option<bool> {
+        self.as_bool()
     }
 
-    fn is_u8(&self) -> bool {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u8::MAX as i64))
-            .is_some()
+    fn as_number(&self) -> Option<Number> {
+        match self {
+            Value::Integer(i) => Some(Number::from(*i)),
+            Value::Float(f) => Some(Number::from(*f)),
+            _ => None,
+        }
     }
 
-    fn is_u16(&self) -> bool {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u16::MAX as i64))
-            .is_some()
+    fn as_str(&self) -> Option<&str> {
+        self.as_str()
     }
 
-    fn is_u32(&self) -> bool {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u32::MAX as i64))
-            .is_some()
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        self.as_array().map(|a| a as &dyn ConfigElementListType)
     }
 
-    fn is_u64(&self) -> bool {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| *i < (u64::MAX as i64))
-            .is_some()
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        self.as_table().map(|t| t as &dyn ConfigElementMapType)
     }
 
-    fn is_f32(&self) -> bool {
-        self.as_float()
-            .filter(|f| *f < (f32::MAX as f64) && *f > (f32::MIN as f64))
-            .is_some()
+    fn as_datetime(&self) -> Option<Datetime> {
+        Value::as_datetime(self).map(Datetime::from)
     }
+}
 
-    fn is_f64(&self) -> bool {
-        self.is_float()
+impl From<Value> for super::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Boolean(b) => super::Value::Bool(b),
+            Value::Integer(i) => super::Value::Number(Number::from(i)),
+            Value::Float(f) => super::Value::Number(Number::from(f)),
+            Value::String(s) => super::Value::Str(s),
+            Value::Datetime(d) => super::Value::Datetime(Datetime::from(&d)),
+            Value::Array(a) => super::Value::List(a.into_iter().map(Self::from).collect()),
+            Value::Table(t) => super::Value::Map(
+                t.into_iter()
+                    .map(|(key, value)| (key, Self::from(value)))
+                    .collect(),
+            ),
+        }
     }
+}
 
-    fn is_str(&self) -> bool {
-        std::matches!(self, Value::String(_))
+impl From<&toml::value::Datetime> for Datetime {
+    fn from(datetime: &toml::value::Datetime) -> Self {
+        Datetime {
+            date: datetime.date.map(|date| super::Date {
+                year: date.year,
+                month: date.month,
+                day: date.day,
+            }),
+            time: datetime.time.map(|time| super::Time {
+                hour: time.hour,
+                minute: time.minute,
+                second: time.second,
+                nanosecond: time.nanosecond,
+            }),
+            offset: datetime.offset.map(|offset| match offset {
+                toml::value::Offset::Z => super::Offset::Z,
+                toml::value::Offset::Custom { minutes } => super::Offset::Custom { minutes },
+            }),
+        }
     }
+}
 
-    fn is_list(&self) -> bool {
-        std::matches!(self, Value::Array(_))
+impl ConfigElementMapType for toml::map::Map<String, toml::Value> {
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        toml::map::Map::get(self, key).map(|t| t as &dyn ConfigElement)
     }
 
-    fn is_map(&self) -> bool {
-        std::matches!(self, Value::Table(_))
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        Box::new(toml::map::Map::iter(self).map(|(k, t)| (k.as_str(), t as &dyn ConfigElement)))
     }
+}
 
-    fn as_bool(&self) -> Option<bool> {
-        self.as_bool()
-    }
+/// Deserialize the [Value] of a parsed TOML document
+///
+/// The document is consumed instead of parsed again, so that its [spans] and [comments] can be
+/// recorded from the same parse.
+pub(crate) fn value(document: toml_edit::ImDocument<&str>) -> Result<Value, toml_edit::de::Error> {
+    serde::Deserialize::deserialize(toml_edit::de::Deserializer::from(document))
+}
 
-    fn as_i8(&self) -> Option<i8> {
-        self.as_integer()
-            .filter(|i| (*i < (i8::MAX as i64)))
-            .map(|i| i as i8)
-    }
+/// Record the spans of the values of a parsed TOML document
+pub(crate) fn spans(document: &toml_edit::ImDocument<&str>) -> Spans {
+    let lines = Lines::new(document.raw());
+    let mut spans = Spans::new();
+    table_spans(document.as_table(), &lines, &mut spans);
+    spans
+}
 
-    fn as_i16(&self) -> Option<i16> {
-        self.as_integer()
-            .filter(|i| (*i < (i16::MAX as i64)))
-            .map(|i| i as i16)
+fn table_spans(table: &toml_edit::Table, lines: &Lines<'_>, spans: &mut Spans) {
+    for (key, item) in table.iter() {
+        let spans = spans.key(key);
+        match item {
+            toml_edit::Item::None => {}
+            toml_edit::Item::Value(value) => value_spans(value, lines, spans),
+            toml_edit::Item::Table(table) => {
+                // Implicitly created tables have no span
+                if let Some(span) = table.span() {
+                    spans.set(lines.span(span));
+                }
+                table_spans(table, lines, spans);
+            }
+            toml_edit::Item::ArrayOfTables(tables) => {
+                if let Some(span) = tables.span() {
+                    spans.set(lines.span(span));
+                }
+                for (index, table) in tables.iter().enumerate() {
+                    let spans = spans.index(index);
+                    if let Some(span) = table.span() {
+                        spans.set(lines.span(span));
+                    }
+                    table_spans(table, lines, spans);
+                }
+            }
+        }
     }
+}
 
-    fn as_i32(&self) -> Option<i32> {
-        self.as_integer()
-            .filter(|i| (*i < (i32::MAX as i64)))
-            .map(|i| i as i32)
+fn value_spans(value: &toml_edit::Value, lines: &Lines<'_>, spans: &mut Spans) {
+    if let Some(span) = value.span() {
+        spans.set(lines.span(span));
     }
 
-    fn as_i64(&self) -> Option<i64> {
-        self.as_integer()
+    match value {
+        toml_edit::Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                value_spans(value, lines, spans.index(index));
+            }
+        }
+        toml_edit::Value::InlineTable(table) => {
+            for (key, value) in table.iter() {
+                value_spans(value, lines, spans.key(key));
+            }
+        }
+        _ => {}
     }
+}
 
-    fn as_u8(&self) -> Option<u8> {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u8::MAX as i64)))
-            .map(|i| i as u8)
-    }
+/// Record the comments of the values of a parsed TOML document
+pub(crate) fn comments(document: &toml_edit::ImDocument<&str>) -> Comments {
+    let mut comments = Comments::new();
+    table_comments(document.as_table(), document.raw(), &mut comments);
+    comments
+}
 
-    fn as_u16(&self) -> Option<u16> {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u16::MAX as i64)))
-            .map(|i| i as u16)
+fn table_comments(table: &toml_edit::Table, document: &str, comments: &mut Comments) {
+    for (key, item) in table.iter() {
+        let comments = comments.key(key);
+        // The comments before a `key = value` line are stored with the key
+        let leading = table
+            .key(key)
+            .and_then(|key| raw(document, key.leaf_decor().prefix()));
+
+        match item {
+            toml_edit::Item::None => {}
+            toml_edit::Item::Value(value) => {
+                value_comments(value, document, leading, None, comments)
+            }
+            toml_edit::Item::Table(table) => {
+                set_comment(comments, document, table.decor());
+                table_comments(table, document, comments);
+            }
+            toml_edit::Item::ArrayOfTables(tables) => {
+                for (index, table) in tables.iter().enumerate() {
+                    let comments = comments.index(index);
+                    set_comment(comments, document, table.decor());
+                    table_comments(table, document, comments);
+                }
+            }
+        }
     }
+}
 
-    fn as_u32(&self) -> Option<u32> {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u32::MAX as i64)))
-            .map(|i| i as u32)
+/// Record the comments of `value` and its children
+///
+/// `leading` are the lines before the value, `after` is the rest of the line after the value
+/// and its separator, if that is not part of the decor of the value.
+fn value_comments(
+    value: &toml_edit::Value,
+    document: &str,
+    leading: Option<&str>,
+    after: Option<&str>,
+    comments: &mut Comments,
+) {
+    let trailing =
+        trailing_comment(raw(document, value.decor().suffix())).or_else(|| trailing_comment(after));
+    let comment = Comment::new(leading_comment(leading), trailing);
+    if comment != Comment::default() {
+        comments.set(comment);
+    }
+
+    match value {
+        toml_edit::Value::Array(array) => {
+            // The decor before an element starts with the rest of the line of the previous element
+            let values: Vec<_> = array.iter().collect();
+            for (index, value) in values.iter().enumerate() {
+                let (_, leading) = split_line(raw(document, value.decor().prefix()));
+                let next = match values.get(index + 1) {
+                    Some(next) => raw(document, next.decor().prefix()),
+                    None => raw(document, Some(array.trailing())),
+                };
+                let (after, _) = split_line(next);
+                value_comments(value, document, leading, after, comments.index(index));
+            }
+        }
+        toml_edit::Value::InlineTable(table) => {
+            for (key, value) in table.iter() {
+                let leading = table
+                    .key(key)
+                    .and_then(|key| raw(document, key.leaf_decor().prefix()));
+                value_comments(value, document, leading, None, comments.key(key));
+            }
+        }
+        _ => {}
     }
+}
 
-    fn as_u64(&self) -> Option<u64> {
-        self.as_integer()
-            .filter(|i| i.is_positive())
-            .filter(|i| (*i < (u64::MAX as i64)))
-            .map(|i| i as u64)
+/// Set the comments of a table header
+fn set_comment(comments: &mut Comments, document: &str, decor: &toml_edit::Decor) {
+    let comment = Comment::new(
+        leading_comment(raw(document, decor.prefix())),
+        trailing_comment(raw(document, decor.suffix())),
+    );
+    if comment != Comment::default() {
+        comments.set(comment);
     }
+}
 
-    fn as_f32(&self) -> Option<f32> {
-        self.as_float()
-            .filter(|f| *f < (f32::MAX as f64) && *f > (f32::MIN as f64))
-            .map(|f| f as f32)
-    }
+/// The text of a whitespace or comment of the parsed `document`
+fn raw<'a>(document: &'a str, raw: Option<&'a toml_edit::RawString>) -> Option<&'a str> {
+    let raw = raw?;
+    raw.as_str().or_else(|| document.get(raw.span()?))
+}
 
-    fn as_f64(&self) -> Option<f64> {
-        self.as_float()
+/// Split `s` into the rest of the current line and the lines after it
+fn split_line(s: Option<&str>) -> (Option<&str>, Option<&str>) {
+    match s.map(|s| s.split_once('\n')) {
+        Some(Some((line, rest))) => (Some(line), Some(rest)),
+        Some(None) => (s, None),
+        None => (None, None),
     }
+}
 
-    fn as_str(&self) -> Option<&str> {
-        self.as_str()
-    }
+/// The comment lines directly before a value, from the whitespace and comments before it
+///
+/// Comments separated from the value by an empty line are not attached to it.
+fn leading_comment(prefix: Option<&str>) -> Option<String> {
+    let prefix = prefix?;
 
-    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
-        self.as_array().map(|a| a as &dyn ConfigElementListType)
+    // The last line is the indentation of the value itself
+    let mut lines = Vec::new();
+    for line in prefix.rsplit('\n').skip(1) {
+        match line.trim().strip_prefix('#') {
+            Some(comment) => lines.push(comment.trim()),
+            None => break,
+        }
     }
 
-    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
-        self.as_table().map(|t| t as &dyn ConfigElementMapType)
+    if lines.is_empty() {
+        return None;
     }
+    lines.reverse();
+    Some(lines.join("\n"))
 }
 
-impl ConfigElementMapType for toml::map::Map<String, toml::Value> {
-    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
-        toml::map::Map::get(self, key).map(|t| t as &dyn ConfigElement)
-    }
-
-    fn keys(&self) -> Vec<String> {
-        toml::map::Map::keys(self).map(String::to_owned).collect()
-    }
+/// The comment after a value on the same line
+fn trailing_comment(suffix: Option<&str>) -> Option<String> {
+    let comment = suffix?.trim().strip_prefix('#')?;
+    Some(comment.trim().to_string())
+}
 
-    fn values(&self) -> Vec<&dyn ConfigElement> {
-        toml::map::Map::values(self)
-            .map(|t| t as &dyn ConfigElement)
-            .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value() {
+        let document = r#"
+name = "app"
+pi = 3.14
+started = 1979-05-27T07:32:00Z
+server = { host = "localhost", ports = [80, 443] }
+
+[a.b]
+enabled = true
+
+[[routes]]
+path = "/"
+"#;
+        let expected: Value = toml::from_str(document).unwrap();
+        assert_eq!(
+            value(toml_edit::ImDocument::parse(document).unwrap()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_spans() {
+        let document = r#"
+name = "app"
+started = 1979-05-27T07:32:00Z
+
+[server]
+ports = [80, 443]
+
+[[routes]]
+path = "/"
+"#;
+        let spans = spans(&toml_edit::ImDocument::parse(document).unwrap());
+        let get = |path: &str| {
+            let accessor = crate::ParsableAccessor::parse(&path).unwrap();
+            spans.get(accessor.remaining()).copied()
+        };
+
+        let name = get("name").unwrap();
+        assert_eq!(&document[name.range()], r#""app""#);
+        assert_eq!((name.line, name.column), (2, 8));
+        assert_eq!(get("started").unwrap().line, 3);
+        assert_eq!(&document[get("server.ports.1").unwrap().range()], "443");
+        assert_eq!(&document[get("routes.0.path").unwrap().range()], r#""/""#);
+    }
+
+    #[test]
+    fn test_comments() {
+        let document = r#"
+# The configuration of the app
+
+# The name of the app
+# Shown in the title bar
+name = "app" # required
+
+[server] # the HTTP server
+ports = [
+    # plain HTTP
+    80,
+    443, # TLS
+]
+limits = { rate = 10 }
+
+# A route
+[[routes]]
+path = "/"
+"#;
+        let comments = comments(&toml_edit::ImDocument::parse(document).unwrap());
+        let get = |path: &str| {
+            let accessor = crate::ParsableAccessor::parse(&path).unwrap();
+            comments.get(accessor.remaining()).cloned()
+        };
+        let comment = |leading: Option<&str>, trailing: Option<&str>| {
+            Some(Comment::new(
+                leading.map(String::from),
+                trailing.map(String::from),
+            ))
+        };
+
+        assert_eq!(
+            get("name"),
+            comment(
+                Some("The name of the app\nShown in the title bar"),
+                Some("required")
+            )
+        );
+        assert_eq!(get("server"), comment(None, Some("the HTTP server")));
+        assert_eq!(get("server.ports.0"), comment(Some("plain HTTP"), None));
+        assert_eq!(get("server.ports.1"), comment(None, Some("TLS")));
+        assert_eq!(get("server.limits"), None);
+        assert_eq!(get("routes.0"), comment(Some("A route"), None));
+        assert_eq!(get("routes.0.path"), None);
     }
 }
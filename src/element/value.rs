@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use super::ConfigElement;
+use super::ConfigElementListType;
+use super::ConfigElementMapType;
+use super::ConfigElementType;
+use super::Datetime;
+use super::Number;
+use crate::config::FromConfigElementError;
+
+/// An owned, format-neutral configuration value
+///
+/// Unlike the values of the format backends, a [Value] can be cloned, compared and hashed, and
+/// values from different formats can be mixed in one tree. Use it to snapshot configuration (see
+/// [to_owned_value](trait.ConfigElement.html#method.to_owned_value)) or to build a configuration
+/// tree programmatically:
+///
+/// ```rust
+/// use config_rs_ng::ConfigElement;
+/// use config_rs_ng::Value;
+///
+/// let value = Value::from_iter([
+///     ("name", Value::from("app")),
+///     ("ports", Value::from(vec![Value::from(80), Value::from(443)])),
+/// ]);
+/// assert_eq!(value.as_map().unwrap().get("name").unwrap().as_str(), Some("app"));
+/// ```
+///
+/// Maps are ordered by their keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(Number),
+    Str(String),
+    Bytes(Vec<u8>),
+    Datetime(Datetime),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl ConfigElement for Value {
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<Number> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn as_datetime(&self) -> Option<Datetime> {
+        match self {
+            Value::Datetime(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        std::matches!(self, Value::Null)
+    }
+}
+
+impl dyn ConfigElement {
+    /// Copy the element and all its children into an owned [Value]
+    ///
+    /// Fails if the tree contains a [custom type](crate::ConfigElementCustomType), which cannot be
+    /// represented by [Value].
+    pub fn to_owned_value(&self) -> Result<Value, FromConfigElementError> {
+        let value = match self.get_type() {
+            ConfigElementType::Null => Value::Null,
+            ConfigElementType::Bool => Value::Bool(self.as_bool().unwrap()),
+            ConfigElementType::I8
+            | ConfigElementType::I16
+            | ConfigElementType::I32
+            | ConfigElementType::I64
+            | ConfigElementType::I128
+            | ConfigElementType::U8
+            | ConfigElementType::U16
+            | ConfigElementType::U32
+            | ConfigElementType::U64
+            | ConfigElementType::U128
+            | ConfigElementType::F32
+            | ConfigElementType::F64 => {
+                // Elements that implement the sized accessors instead of `as_number()`
+                let number = self
+                    .as_number()
+                    .or_else(|| self.as_i128().map(Number::from))
+                    .or_else(|| self.as_u128().map(Number::from))
+                    .or_else(|| self.as_f64().map(Number::from))
+                    .or_else(|| self.as_f32().map(Number::from));
+                Value::Number(number.unwrap())
+            }
+            ConfigElementType::Str => Value::Str(self.as_str().unwrap().to_string()),
+            ConfigElementType::Bytes => Value::Bytes(self.as_bytes().unwrap().to_vec()),
+            ConfigElementType::OffsetDatetime
+            | ConfigElementType::LocalDatetime
+            | ConfigElementType::LocalDate
+            | ConfigElementType::LocalTime => Value::Datetime(self.as_datetime().unwrap()),
+            ConfigElementType::List => {
+                let values = self
+                    .as_list()
+                    .unwrap()
+                    .iter()
+                    .map(|element| element.to_owned_value())
+                    .collect::<Result<_, _>>()?;
+                Value::List(values)
+            }
+            ConfigElementType::Map => {
+                let entries = self
+                    .as_map()
+                    .unwrap()
+                    .entries()
+                    .map(|(key, element)| Ok((key.to_string(), element.to_owned_value()?)))
+                    .collect::<Result<_, FromConfigElementError>>()?;
+                Value::Map(entries)
+            }
+            ConfigElementType::Custom(name) => {
+                return Err(FromConfigElementError::TypeError {
+                    expected: "value",
+                    found: name,
+                })
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl<T> ConfigElementMapType for BTreeMap<String, T>
+where
+    T: ConfigElement,
+{
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        BTreeMap::get(self, key).map(|t| t as &dyn ConfigElement)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        Box::new(BTreeMap::iter(self).map(|(k, t)| (k.as_str(), t as &dyn ConfigElement)))
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(n: $t) -> Self {
+                    Value::Number(n.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+impl From<Number> for Value {
+    fn from(n: Number) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<Datetime> for Value {
+    fn from(d: Datetime) -> Self {
+        Value::Datetime(d)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(l: Vec<Value>) -> Self {
+        Value::List(l)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(m: BTreeMap<String, Value>) -> Self {
+        Value::Map(m)
+    }
+}
+
+impl<K: Into<String>> FromIterator<(K, Value)> for Value {
+    /// Collect key-value pairs into a [Value::Map]
+    fn from_iter<I: IntoIterator<Item = (K, Value)>>(iter: I) -> Self {
+        Value::Map(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+}
+
+impl FromIterator<Value> for Value {
+    /// Collect values into a [Value::List]
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Value::List(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_to_owned_value() {
+        let list = vec![Value::from(1), Value::from(-2.5), Value::Null];
+        let value = Value::from_iter([("list", Value::from(list)), ("flag", Value::from(true))]);
+
+        let element: &dyn ConfigElement = &value;
+        assert_eq!(element.to_owned_value().unwrap(), value);
+    }
+
+    #[test]
+    fn test_hash() {
+        let set: HashSet<Value> = [Value::from(0.0), Value::from(-0.0), Value::from(0)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "json", feature = "toml"))]
+    fn test_mixed_formats() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{ "port": 8080, "hosts": ["a", "b"], "big": 18446744073709551615 }"#,
+        )
+        .unwrap();
+        let toml: toml::Value =
+            toml::from_str("port = 8080\nhosts = [\"a\", \"b\"]\nwhen = 1979-05-27").unwrap();
+
+        let json = Value::from(json);
+        let toml = Value::from(toml);
+        let get = |v: &Value, key: &str| v.as_map().unwrap().get(key).unwrap().to_owned_value();
+
+        assert_eq!(get(&json, "port").unwrap(), get(&toml, "port").unwrap());
+        assert_eq!(get(&json, "hosts").unwrap(), get(&toml, "hosts").unwrap());
+        assert_eq!(get(&json, "big").unwrap(), Value::from(u64::MAX));
+        assert_eq!(
+            get(&toml, "when").unwrap(),
+            Value::from("1979-05-27".parse::<Datetime>().unwrap())
+        );
+        assert_ne!(json, toml);
+    }
+}
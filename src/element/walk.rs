@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use super::ConfigElement;
+use crate::accessor::AccessType;
+use crate::accessor::Accessor;
+
+/// The children of an element: the step to each child, and the child in every layer that
+/// contributes to it, from the highest to the lowest priority
+type Children<'a> = std::vec::IntoIter<(AccessType, Vec<&'a dyn ConfigElement>)>;
+
+/// A depth-first walk over a tree of [ConfigElement]s
+///
+/// Yields the path and the element for every element below the root of the walk. Maps and lists
+/// are yielded before their children, the children in the order of
+/// [ConfigElementMapType::entries](crate::ConfigElementMapType::entries) and
+/// [ConfigElementListType::iter](crate::ConfigElementListType::iter).
+///
+/// Created by [walk](trait.ConfigElement.html#method.walk) for a single tree, or by
+/// [Layers::walk](crate::Layers::walk) for the merged view of all layers.
+///
+/// ```rust
+/// use config_rs_ng::ConfigElement;
+/// use config_rs_ng::Value;
+///
+/// let value = Value::from_iter([
+///     ("a", Value::from_iter([("b", Value::from(vec![Value::from(1)]))])),
+/// ]);
+/// let element: &dyn ConfigElement = &value;
+///
+/// let paths: Vec<String> = element.walk().map(|(path, _)| path.to_string()).collect();
+/// assert_eq!(paths, ["a", "a.b", "a.b.0"]);
+/// ```
+pub struct Walk<'a> {
+    path: Vec<AccessType>,
+    stack: Vec<Children<'a>>,
+}
+
+impl<'a> Walk<'a> {
+    /// Walk the merged view of `layers`, given from the highest to the lowest priority
+    ///
+    /// The merged view is the one [Layers::get](crate::Layers::get) returns values from: the
+    /// element at a path is the one of the highest layer that contains the path. The children of a
+    /// map are the keys of that map and of the maps at the same path in the layers below, down to
+    /// the first layer that contains something else than a map. Lists are merged by index in the
+    /// same way.
+    pub(crate) fn new(layers: Vec<&'a dyn ConfigElement>) -> Self {
+        Walk {
+            path: Vec::new(),
+            stack: children(&layers).into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (Accessor, &'a dyn ConfigElement);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.stack.len();
+            let (step, layers) = match self.stack.last_mut()?.next() {
+                Some(child) => child,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+
+            self.path.truncate(depth - 1);
+            self.path.push(step);
+            if let Some(children) = children(&layers) {
+                self.stack.push(children);
+            }
+            return Some((Accessor::new(self.path.clone()), layers[0]));
+        }
+    }
+}
+
+/// The merged children of `layers`, or `None` if the highest layer is neither a map nor a list
+fn children<'a>(layers: &[&'a dyn ConfigElement]) -> Option<Children<'a>> {
+    let first = layers.first()?;
+
+    let children = if first.is_map() {
+        let maps: Vec<_> = layers.iter().map_while(|layer| layer.as_map()).collect();
+
+        let mut seen = HashSet::new();
+        maps.iter()
+            .flat_map(|map| map.keys_iter())
+            .filter(|key| seen.insert(*key))
+            .map(|key| {
+                let elements = maps.iter().filter_map(|map| map.get(key)).collect();
+                (AccessType::Key(key.to_string()), elements)
+            })
+            .collect::<Vec<_>>()
+    } else if first.is_list() {
+        let lists: Vec<_> = layers.iter().map_while(|layer| layer.as_list()).collect();
+
+        let len = lists
+            .iter()
+            .map(|list| list.len())
+            .max()
+            .unwrap_or_default();
+        (0..len)
+            .map(|index| {
+                let elements = lists
+                    .iter()
+                    .filter_map(|list| list.at_index(index))
+                    .collect();
+                (AccessType::Index(index), elements)
+            })
+            .collect::<Vec<_>>()
+    } else {
+        return None;
+    };
+
+    Some(children.into_iter())
+}
+
+impl dyn ConfigElement {
+    /// Walk all elements below this element depth-first, see [Walk]
+    pub fn walk(&self) -> Walk<'_> {
+        Walk::new(vec![self])
+    }
+
+    /// Walk all elements below this element that are neither maps nor lists
+    ///
+    /// This yields one `path = value` pair per value, e.g. for dumping the configuration:
+    ///
+    /// ```rust
+    /// # use config_rs_ng::ConfigElement;
+    /// # use config_rs_ng::Value;
+    /// let value = Value::from_iter([("a", Value::from(vec![Value::from("x")]))]);
+    /// let element: &dyn ConfigElement = &value;
+    ///
+    /// for (path, value) in element.flatten() {
+    ///     println!("{} = {:?}", path, value.as_str());
+    /// }
+    /// ```
+    pub fn flatten(&self) -> impl Iterator<Item = (Accessor, &dyn ConfigElement)> {
+        self.walk()
+            .filter(|(_, element)| !element.is_map() && !element.is_list())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    fn paths(walk: impl Iterator<Item = (Accessor, &'static dyn ConfigElement)>) -> Vec<String> {
+        walk.map(|(path, _)| path.to_string()).collect()
+    }
+
+    fn leak(value: Value) -> &'static dyn ConfigElement {
+        Box::leak(Box::new(value))
+    }
+
+    #[test]
+    fn test_walk() {
+        let element = leak(Value::from_iter([
+            (
+                "a",
+                Value::from_iter([("b", Value::from(vec![Value::from(1), Value::from(2)]))]),
+            ),
+            ("c", Value::from("x")),
+            ("d", Value::from_iter(Vec::<(String, Value)>::new())),
+        ]));
+
+        assert_eq!(
+            paths(element.walk()),
+            ["a", "a.b", "a.b.0", "a.b.1", "c", "d"]
+        );
+        assert_eq!(paths(element.flatten()), ["a.b.0", "a.b.1", "c"]);
+        assert_eq!(paths(leak(Value::from(true)).walk()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_walk_layers() {
+        let high = leak(Value::from_iter([
+            ("a", Value::from_iter([("x", Value::from(1))])),
+            ("b", Value::from("shadows")),
+            ("l", Value::from(vec![Value::from(10)])),
+        ]));
+        let low = leak(Value::from_iter([
+            ("a", Value::from_iter([("y", Value::from(2))])),
+            ("b", Value::from_iter([("hidden", Value::from(3))])),
+            ("l", Value::from(vec![Value::from(20), Value::from(21)])),
+            ("z", Value::from(4)),
+        ]));
+
+        let flattened: Vec<(String, Value)> = Walk::new(vec![high, low])
+            .filter(|(_, element)| !element.is_map() && !element.is_list())
+            .map(|(path, element)| (path.to_string(), element.to_owned_value().unwrap()))
+            .collect();
+        assert_eq!(
+            flattened,
+            [
+                ("a.x".to_string(), Value::from(1)),
+                ("a.y".to_string(), Value::from(2)),
+                ("b".to_string(), Value::from("shadows")),
+                ("l.0".to_string(), Value::from(10)),
+                ("l.1".to_string(), Value::from(21)),
+                ("z".to_string(), Value::from(4)),
+            ]
+        );
+    }
+}
@@ -5,7 +5,16 @@
 //! It lets you merge configuration from a variety of sources:
 //!
 //! * String literals
-//! * Files: TOML, JSON
+//! * Files: TOML, JSON, optionally compressed with gzip, zstd or xz
+//! * Files from git repositories
+//! * Encrypted files: age, SOPS
+//! * Output of programs
+//! * Standard input
+//! * HTTP(S) URLs
+//! * Key-value stores: etcd, Consul, Redis
+//! * Secrets from HashiCorp Vault
+//! * SQLite tables
+//! * systemd credentials
 //! * Manual/programmatic override
 //!
 //! It also lets you define custom configuration sources, either "sync" or "async".
@@ -73,50 +82,156 @@
 //! instead of [Layers::get](crate::Layers::get), you will (on success) get a
 //! [ConfigView](crate::ConfigView).
 //!
-//! This object contains meta information for the configuration value you're trying to access.
-//! Right now, it only contains a [ConfigSourceDescription](crate::ConfigSourceDescription), which
-//! describes from what source the value comes from (e.g. a `PathBuf`).
+//! This object contains meta information for the configuration value you're trying to access:
+//! a [ConfigSourceDescription](crate::ConfigSourceDescription), which describes from what source
+//! the value comes from (e.g. a `PathBuf`), and the [Span](crate::Span) of the value in that
+//! source (byte range, line and column), if the format parser recorded it. The TOML and JSON
+//! parsers record spans for all values.
 //!
-//! Later, span information might be supported as well (TODO).
+//! Format parsers may also record the [Comment](crate::Comment)s before and after a value, which
+//! are returned from [ConfigView::comment](crate::ConfigView::comment). The TOML parser records
+//! comments.
 //!
 
 mod accessor;
+mod comment;
 mod config;
 mod description;
 mod element;
 mod object;
+mod path_map;
 mod source;
+mod span;
 
 pub use config_rs_ng_derive::ConfigConstructor;
 pub use config_rs_ng_derive::FromConfigElement;
 
+/// Embed a configuration file in the binary, checking its syntax at compile time
+///
+/// The macro reads the file at compile time, parses it and emits a compile error with the line
+/// and column of the first syntax error. On success, it evaluates to a
+/// [StringSource](crate::StringSource) with the content of the file, for example for loading
+/// defaults with [ConfigBuilder::load_default](crate::ConfigBuilder::load_default).
+///
+/// Relative paths are resolved from the directory of the `Cargo.toml` of the crate. The format is
+/// detected from the file extension, or specified with `format = toml` or `format = json`.
+///
+/// With `check = T`, the content is additionally constructed into `T` with
+/// [ConfigConstructor](crate::ConfigConstructor) when the source is loaded (see
+/// [StringSource::validate](crate::StringSource::validate)). This check cannot happen at compile
+/// time, as a procedural macro does not know about the types of the crate it is used in.
+///
+/// ```rust
+/// use config_rs_ng::Config;
+/// use config_rs_ng::ConfigConstructor;
+///
+/// #[derive(Debug, ConfigConstructor)]
+/// struct Settings {
+///     foo: i64,
+/// }
+///
+/// let config = Config::builder()
+///     .load_default(Box::new(config_rs_ng::include_config!(
+///         "examples/file.json",
+///         check = Settings
+///     )))
+///     .build()
+///     .expect("Building configuration object");
+///
+/// let settings = Settings::construct_from(config.layers()).unwrap();
+/// assert_eq!(settings.foo, 1);
+/// ```
+#[cfg(any(feature = "json", feature = "toml"))]
+pub use config_rs_ng_derive::include_config;
+
 pub use crate::accessor::AccessType;
 pub use crate::accessor::Accessor;
 pub use crate::accessor::ParsableAccessor;
+pub use crate::comment::Comment;
+pub use crate::comment::Comments;
 #[cfg(feature = "async")]
 pub use crate::config::AsyncConfig;
 #[cfg(feature = "async")]
 pub use crate::config::AsyncConfigBuilder;
+#[cfg(feature = "base64")]
+pub use crate::config::Base64;
 pub use crate::config::Config;
 pub use crate::config::ConfigBuilder;
 pub use crate::config::ConfigConstructor;
+pub use crate::config::ConfigError;
 pub use crate::config::FromConfigElement;
 pub use crate::config::FromConfigElementError;
+pub use crate::config::Hex;
 pub use crate::config::Layers;
+pub use crate::config::ValueCipher;
 pub use crate::description::ConfigSourceDescription;
 pub use crate::element::ConfigElement;
+pub use crate::element::ConfigElementCustomType;
 pub use crate::element::ConfigElementListType;
 pub use crate::element::ConfigElementMapType;
+pub use crate::element::ConfigElementType;
+pub use crate::element::Date;
+pub use crate::element::Datetime;
+pub use crate::element::DatetimeParseError;
+pub use crate::element::Number;
+pub use crate::element::NumberParseError;
+pub use crate::element::Offset;
+pub use crate::element::Time;
+pub use crate::element::Value;
+pub use crate::element::Walk;
 pub use crate::object::ConfigObject;
 pub use crate::object::ConfigView;
+#[cfg(feature = "age")]
+pub use crate::source::AgeCipher;
+#[cfg(feature = "age")]
+pub use crate::source::AgeKey;
+#[cfg(feature = "age")]
+pub use crate::source::AgeSource;
+#[cfg(feature = "async")]
+pub use crate::source::AsyncConfigSource;
+#[cfg(feature = "http-blocking")]
+pub use crate::source::BlockingHttpSource;
+#[cfg(feature = "json")]
+pub use crate::source::CachedSource;
+pub use crate::source::CommandSource;
+pub use crate::source::Compression;
 pub use crate::source::ConfigSource;
+#[cfg(feature = "consul")]
+pub use crate::source::ConsulSource;
+#[cfg(feature = "etcd")]
+pub use crate::source::EtcdSource;
 pub use crate::source::FileSource;
 pub use crate::source::FormatParser;
+#[cfg(feature = "git")]
+pub use crate::source::GitSource;
+#[cfg(feature = "http")]
+pub use crate::source::HttpSource;
+#[cfg(feature = "redis")]
+pub use crate::source::RedisSource;
+pub use crate::source::SecretFileSource;
+#[cfg(feature = "sops")]
+pub use crate::source::SopsFormat;
+#[cfg(feature = "sops")]
+pub use crate::source::SopsSource;
 pub use crate::source::SourceError;
+#[cfg(feature = "sqlite")]
+pub use crate::source::SqliteSource;
+pub use crate::source::StdinSource;
 pub use crate::source::StringSource;
+pub use crate::source::SystemdCredentialsSource;
+#[cfg(feature = "vault")]
+pub use crate::source::VaultAuth;
+#[cfg(feature = "vault")]
+pub use crate::source::VaultLease;
+#[cfg(feature = "vault")]
+pub use crate::source::VaultSource;
 
 #[cfg(feature = "json")]
 pub use crate::source::JsonFormatParser;
 
 #[cfg(feature = "toml")]
 pub use crate::source::TomlFormatParser;
+
+pub use crate::path_map::PathMap;
+pub use crate::span::Span;
+pub use crate::span::Spans;
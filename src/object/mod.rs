@@ -1,6 +1,10 @@
 use crate::accessor::Accessor;
+use crate::comment::Comment;
+use crate::comment::Comments;
 use crate::description::ConfigSourceDescription;
 use crate::element::ConfigElement;
+use crate::span::Span;
+use crate::span::Spans;
 
 /// A [ConfigObject] is a parsed piece of configuration
 ///
@@ -12,13 +16,55 @@ use crate::element::ConfigElement;
 #[derive(Debug)]
 pub struct ConfigObject {
     element: Box<dyn ConfigElement>,
-    #[allow(unused)] // TODO
     source: ConfigSourceDescription,
+    spans: Spans,
+    comments: Comments,
 }
 
 impl ConfigObject {
     pub fn new(element: Box<dyn ConfigElement>, source: ConfigSourceDescription) -> Self {
-        Self { element, source }
+        Self {
+            element,
+            source,
+            spans: Spans::new(),
+            comments: Comments::new(),
+        }
+    }
+
+    /// Set the spans of the values of the element, as recorded by
+    /// [FormatParser::parse_with_spans](crate::FormatParser::parse_with_spans)
+    pub fn with_spans(mut self, spans: Spans) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// Set the comments of the values of the element, as recorded by
+    /// [FormatParser::parse_with_comments](crate::FormatParser::parse_with_comments)
+    pub fn with_comments(mut self, comments: Comments) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    /// The description of the source this object was loaded from
+    pub fn description(&self) -> &ConfigSourceDescription {
+        &self.source
+    }
+
+    pub(crate) fn element(&self) -> &dyn ConfigElement {
+        &*self.element
+    }
+
+    /// Replace the element, keeping the description, spans and comments
+    pub(crate) fn try_map_element<F, E>(self, f: F) -> Result<Self, E>
+    where
+        F: FnOnce(Box<dyn ConfigElement>) -> Result<Box<dyn ConfigElement>, E>,
+    {
+        Ok(ConfigObject {
+            element: f(self.element)?,
+            source: self.source,
+            spans: self.spans,
+            comments: self.comments,
+        })
     }
 
     pub(crate) fn get(
@@ -39,11 +85,15 @@ impl ConfigObject {
         &'a self,
         accessor: &mut Accessor,
     ) -> Result<Option<ConfigView<'a>>, ConfigObjectAccessError> {
+        let span = self.spans.get(accessor.remaining()).copied();
+        let comment = self.comments.get(accessor.remaining());
         if let Some(element) = self.get(accessor)? {
             Ok(Some({
                 ConfigView {
                     element,
-                    desc: &self.source,
+                    desc: element.source_description().unwrap_or(&self.source),
+                    span: element.source_description().map_or(span, |_| None),
+                    comment: element.source_description().map_or(comment, |_| None),
                 }
             }))
         } else {
@@ -69,6 +119,8 @@ pub enum ConfigObjectAccessError {
     AccessWithKeyOnI32(String),
     #[error("Accessed i64 with key '{0}'")]
     AccessWithKeyOnI64(String),
+    #[error("Accessed i128 with key '{0}'")]
+    AccessWithKeyOnI128(String),
     #[error("Accessed u8 with key '{0}'")]
     AccessWithKeyOnU8(String),
     #[error("Accessed u16 with key '{0}'")]
@@ -77,14 +129,22 @@ pub enum ConfigObjectAccessError {
     AccessWithKeyOnU32(String),
     #[error("Accessed u64 with key '{0}'")]
     AccessWithKeyOnU64(String),
+    #[error("Accessed u128 with key '{0}'")]
+    AccessWithKeyOnU128(String),
     #[error("Accessed f32 with key '{0}'")]
     AccessWithKeyOnF32(String),
     #[error("Accessed f64 with key '{0}'")]
     AccessWithKeyOnF64(String),
     #[error("Accessed String with key '{0}'")]
     AccessWithKeyOnStr(String),
+    #[error("Accessed Bytes with key '{0}'")]
+    AccessWithKeyOnBytes(String),
     #[error("Accessed List with key '{0}'")]
     AccessWithKeyOnList(String),
+    #[error("Accessed Datetime with key '{0}'")]
+    AccessWithKeyOnDatetime(String),
+    #[error("Accessed {0} with key '{1}'")]
+    AccessWithKeyOnCustom(&'static str, String),
 
     #[error("Accessed Null with index '{0}'")]
     AccessWithIndexOnNull(usize),
@@ -98,6 +158,8 @@ pub enum ConfigObjectAccessError {
     AccessWithIndexOnI32(usize),
     #[error("Accessed i64 with index '{0}'")]
     AccessWithIndexOnI64(usize),
+    #[error("Accessed i128 with index '{0}'")]
+    AccessWithIndexOnI128(usize),
     #[error("Accessed u8 with index '{0}'")]
     AccessWithIndexOnU8(usize),
     #[error("Accessed u16 with index '{0}'")]
@@ -106,14 +168,22 @@ pub enum ConfigObjectAccessError {
     AccessWithIndexOnU32(usize),
     #[error("Accessed u64 with index '{0}'")]
     AccessWithIndexOnU64(usize),
+    #[error("Accessed u128 with index '{0}'")]
+    AccessWithIndexOnU128(usize),
     #[error("Accessed f32 with index '{0}'")]
     AccessWithIndexOnF32(usize),
     #[error("Accessed f64 with index '{0}'")]
     AccessWithIndexOnF64(usize),
     #[error("Accessed usize with index '{0}'")]
     AccessWithIndexOnStr(usize),
+    #[error("Accessed Bytes with index '{0}'")]
+    AccessWithIndexOnBytes(usize),
     #[error("Accessed Map with index '{0}'")]
     AccessWithIndexOnMap(usize),
+    #[error("Accessed Datetime with index '{0}'")]
+    AccessWithIndexOnDatetime(usize),
+    #[error("Accessed {0} with index '{1}'")]
+    AccessWithIndexOnCustom(&'static str, usize),
 }
 
 /// An object that can be used to get a configuration value or the description of the source of
@@ -122,6 +192,8 @@ pub enum ConfigObjectAccessError {
 pub struct ConfigView<'a> {
     element: &'a dyn ConfigElement,
     desc: &'a ConfigSourceDescription,
+    span: Option<Span>,
+    comment: Option<&'a Comment>,
 }
 
 impl<'a> ConfigView<'a> {
@@ -132,4 +204,16 @@ impl<'a> ConfigView<'a> {
     pub fn description(&self) -> &ConfigSourceDescription {
         self.desc
     }
+
+    /// The position of the value in the document described by [ConfigView::description], if the
+    /// format parser recorded it
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// The comments around the value in the document described by [ConfigView::description], if
+    /// the format parser recorded them
+    pub fn comment(&self) -> Option<&Comment> {
+        self.comment
+    }
 }
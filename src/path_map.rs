@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use crate::accessor::AccessType;
+
+/// Values attached to the positions of a parsed document, by their path in the document
+///
+/// This is the tree behind [Spans](crate::Spans) and [Comments](crate::Comments).
+#[derive(Clone, Debug)]
+pub struct PathMap<T> {
+    value: Option<T>,
+    keys: BTreeMap<String, PathMap<T>>,
+    indices: BTreeMap<usize, PathMap<T>>,
+}
+
+impl<T> PathMap<T> {
+    pub fn new() -> Self {
+        PathMap {
+            value: None,
+            keys: BTreeMap::new(),
+            indices: BTreeMap::new(),
+        }
+    }
+
+    /// Set the value at this position
+    pub fn set(&mut self, value: T) {
+        self.value = Some(value);
+    }
+
+    /// The map of the value at `key` of the map at this position
+    pub fn key(&mut self, key: &str) -> &mut PathMap<T> {
+        self.keys.entry(key.to_string()).or_default()
+    }
+
+    /// The map of the value at `index` of the list at this position
+    pub fn index(&mut self, index: usize) -> &mut PathMap<T> {
+        self.indices.entry(index).or_default()
+    }
+
+    /// Get the value at `path`
+    pub(crate) fn get(&self, path: &[AccessType]) -> Option<&T> {
+        let mut map = self;
+        for step in path {
+            map = match step {
+                AccessType::Key(key) => map.keys.get(key)?,
+                AccessType::Index(index) => map.indices.get(index)?,
+            };
+        }
+        map.value.as_ref()
+    }
+}
+
+impl<T> Default for PathMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
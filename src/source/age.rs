@@ -0,0 +1,242 @@
+//! age-encrypted configuration sources
+
+use std::fmt::Debug;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::FormatParser;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+/// Where the age identities (private keys) used for decryption are read from
+///
+/// The key material has the format of an age identity file: one `AGE-SECRET-KEY-1...` per line,
+/// with empty lines and lines starting with `#` being ignored. It is read each time a source is
+/// loaded.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum AgeKey {
+    /// An identity file, as created by `age-keygen`
+    File(PathBuf),
+
+    /// An environment variable that contains the identities, like `SOPS_AGE_KEY`
+    Env(String),
+}
+
+impl AgeKey {
+    /// Read the identities from the file at `path`
+    pub fn from_file<Pa: AsRef<Path>>(path: Pa) -> Self {
+        AgeKey::File(path.as_ref().to_path_buf())
+    }
+
+    /// Read the identities from the environment variable `var`
+    pub fn from_env<V: Into<String>>(var: V) -> Self {
+        AgeKey::Env(var.into())
+    }
+
+    pub(crate) fn identities(&self) -> Result<Vec<Box<dyn age::Identity>>, SourceError> {
+        let (content, origin) = match self {
+            AgeKey::File(path) => (
+                std::fs::read_to_string(path)?,
+                format!("file '{}'", path.display()),
+            ),
+            AgeKey::Env(var) => (
+                std::env::var(var).map_err(|_| SourceError::EnvVarNotSet(var.clone()))?,
+                format!("environment variable '{}'", var),
+            ),
+        };
+
+        let identities = age::IdentityFile::from_buffer(content.as_bytes())
+            .and_then(|file| {
+                file.into_identities()
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            })
+            .map_err(|_| SourceError::AgeNoIdentity(origin.clone()))?;
+
+        if identities.is_empty() {
+            return Err(SourceError::AgeNoIdentity(origin));
+        }
+        Ok(identities)
+    }
+}
+
+/// Decrypt age-encrypted content, which may be ASCII-armored or binary
+pub(crate) fn decrypt(
+    identities: &[Box<dyn age::Identity>],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, SourceError> {
+    let decryptor = age::Decryptor::new(age::armor::ArmoredReader::new(ciphertext))?;
+    let mut reader = decryptor.decrypt(identities.iter().map(|i| i.as_ref()))?;
+
+    let mut plaintext = Vec::new();
+    reader.read_to_end(&mut plaintext)?;
+    Ok(plaintext)
+}
+
+/// A [ValueCipher](crate::ValueCipher) for inline values encrypted with age
+///
+/// Handles values of the form `ENC[age:<data>]`, where `<data>` is the base64-encoded (binary)
+/// age ciphertext, as created by `age -r <recipient> | base64 -w0`. The identities are read from
+/// the [AgeKey] each time a value is decrypted.
+#[derive(Debug, Clone)]
+pub struct AgeCipher {
+    key: AgeKey,
+}
+
+impl AgeCipher {
+    pub fn new(key: AgeKey) -> Self {
+        AgeCipher { key }
+    }
+
+    /// Decrypt values with the identities from the identity file at `path`
+    pub fn from_file<Pa: AsRef<Path>>(path: Pa) -> Self {
+        AgeCipher::new(AgeKey::from_file(path))
+    }
+}
+
+impl crate::ValueCipher for AgeCipher {
+    fn scheme(&self) -> &str {
+        "age"
+    }
+
+    fn decrypt(&self, data: &str) -> Result<String, SourceError> {
+        use base64::Engine;
+
+        let ciphertext = base64::engine::general_purpose::STANDARD.decode(data.trim())?;
+        let plaintext = decrypt(&self.key.identities()?, &ciphertext)?;
+        String::from_utf8(plaintext).map_err(|e| SourceError::Custom(Box::new(e)))
+    }
+}
+
+/// A source that reads an age-encrypted file and parses the decrypted content with `P`
+///
+/// The file may be ASCII-armored (`age --armor`) or binary.
+#[derive(Debug)]
+pub struct AgeSource<P>
+where
+    P: FormatParser + std::fmt::Debug,
+{
+    path: PathBuf,
+    key: AgeKey,
+    _pd: std::marker::PhantomData<P>,
+}
+
+impl<P: FormatParser> AgeSource<P> {
+    pub fn new<Pa: AsRef<Path>>(path: Pa, key: AgeKey) -> Result<Self, SourceError> {
+        Ok(AgeSource {
+            path: path.as_ref().to_path_buf(),
+            key,
+            _pd: std::marker::PhantomData,
+        })
+    }
+
+    fn parse(&self, ciphertext: Vec<u8>) -> Result<ConfigObject, SourceError>
+    where
+        <P as FormatParser>::Output: 'static,
+    {
+        let plaintext = decrypt(&self.key.identities()?, &ciphertext)?;
+        let (element, spans, comments) = P::parse_with_comments(plaintext)?;
+
+        let desc = ConfigSourceDescription::Path(self.path.clone());
+        Ok(ConfigObject::new(Box::new(element), desc)
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+}
+
+impl<P> ConfigSource for AgeSource<P>
+where
+    P: FormatParser + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        self.parse(std::fs::read(&self.path)?)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<P> crate::source::AsyncConfigSource for AgeSource<P>
+where
+    P: FormatParser + Send + Sync + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        self.parse(tokio::fs::read(&self.path).await?)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use age::secrecy::ExposeSecret;
+
+    use super::*;
+    use crate::source::JsonFormatParser;
+
+    #[test]
+    fn test_age_source() {
+        let identity = age::x25519::Identity::generate();
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            key_file.path(),
+            format!("# test key\n{}\n", identity.to_string().expose_secret()),
+        )
+        .unwrap();
+
+        let ciphertext =
+            age::encrypt_and_armor(&identity.to_public(), br#"{"password": "hunter2"}"#).unwrap();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), ciphertext).unwrap();
+
+        let source =
+            AgeSource::<JsonFormatParser>::new(file.path(), AgeKey::from_file(key_file.path()))
+                .unwrap();
+        let object = source.load().unwrap();
+        let mut accessor = crate::ParsableAccessor::parse(&"password").unwrap();
+        let value = object.get(&mut accessor).unwrap().unwrap();
+        assert_eq!(value.as_str(), Some("hunter2"));
+
+        let other = age::x25519::Identity::generate();
+        std::fs::write(key_file.path(), other.to_string().expose_secret()).unwrap();
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::AgeDecrypt(age::DecryptError::NoMatchingKeys))
+        ));
+
+        std::fs::write(key_file.path(), "# no keys\n").unwrap();
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::AgeNoIdentity(_))
+        ));
+    }
+
+    #[test]
+    fn test_age_cipher() {
+        use base64::Engine;
+
+        let identity = age::x25519::Identity::generate();
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), identity.to_string().expose_secret()).unwrap();
+
+        let ciphertext = age::encrypt(&identity.to_public(), b"hunter2").unwrap();
+        let encrypted = format!(
+            "ENC[age:{}]",
+            base64::engine::general_purpose::STANDARD.encode(ciphertext)
+        );
+        let json = format!(r#"{{"user": "app", "password": "{}"}}"#, encrypted);
+
+        let config = crate::Config::builder()
+            .load(Box::new(
+                crate::StringSource::<JsonFormatParser>::new(json).unwrap(),
+            ))
+            .decrypt_values(Box::new(AgeCipher::from_file(key_file.path())))
+            .build()
+            .unwrap();
+        let password = config.layers().get("password").unwrap().unwrap();
+        assert_eq!(password.as_str(), Some("hunter2"));
+    }
+}
@@ -0,0 +1,418 @@
+//! Falling back to the last successfully loaded configuration of a source
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::description::ConfigSourceDescription;
+use crate::element::Datetime;
+use crate::element::Number;
+use crate::element::Value;
+use crate::object::ConfigObject;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+/// A source that keeps the last successfully loaded configuration of another source in a cache
+/// file, and falls back to it if loading the other source fails
+///
+/// Each time the inner source loads successfully, its configuration tree is written to the cache
+/// file as JSON, with each value tagged with its type so that bytes, datetimes and large numbers
+/// are restored unchanged. If loading the inner source fails and the cache file exists, the configuration
+/// from the cache file is used instead, with a [ConfigSourceDescription::Cached] description that
+/// holds the error of the inner source. Use [Layers::descriptions](crate::Layers::descriptions)
+/// to detect that a configuration is stale.
+///
+/// The cache file is only readable by its owner on Unix. If the cache file cannot be read or
+/// parsed, the error of the inner source is returned. Failing to write
+/// the cache file does not fail loading; configurations with
+/// [custom types](crate::ConfigElementCustomType) cannot be cached.
+///
+/// # Note
+///
+/// The cache holds the plain configuration, including all secrets it contains. Per-value
+/// descriptions of the inner source are not cached.
+#[derive(Debug)]
+pub struct CachedSource<S> {
+    source: S,
+    path: PathBuf,
+}
+
+impl<S> CachedSource<S> {
+    /// Cache the configuration of `source` in the file at `path`
+    pub fn new<Pa: AsRef<Path>>(source: S, path: Pa) -> Result<Self, SourceError> {
+        Ok(CachedSource {
+            source,
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Serialize the configuration of `object`
+    fn serialize(object: &ConfigObject) -> Result<Vec<u8>, SourceError> {
+        let value = object
+            .element()
+            .to_owned_value()
+            .map_err(|e| SourceError::Uncacheable(Box::new(e)))?;
+        Ok(serde_json::to_vec_pretty(&CachedValue::from(value))?)
+    }
+
+    /// The path of the temporary file the cache is written to before it is moved into place
+    fn tmp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+
+    /// The options to create the temporary file with, so that only the owner can read the cache
+    fn open_options() -> std::fs::OpenOptions {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+    }
+
+    fn write_cache(&self, buf: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let tmp = self.tmp_path();
+        Self::open_options().open(&tmp)?.write_all(buf)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+
+    fn load_cache(&self, buf: Vec<u8>, error: SourceError) -> Result<ConfigObject, SourceError> {
+        let value = match serde_json::from_slice::<CachedValue>(&buf) {
+            Ok(value) => Value::from(value),
+            Err(_e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(path = %self.path.display(), error = %_e, "Failed to parse cache");
+                return Err(error);
+            }
+        };
+        let desc = ConfigSourceDescription::Cached {
+            path: self.path.clone(),
+            error: error.to_string(),
+        };
+        Ok(ConfigObject::new(Box::new(value), desc))
+    }
+}
+
+impl<S: ConfigSource> ConfigSource for CachedSource<S> {
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        match self.source.load() {
+            Ok(object) => {
+                let written = Self::serialize(&object)
+                    .and_then(|buf| self.write_cache(&buf).map_err(SourceError::from));
+                if let Err(_e) = written {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(path = %self.path.display(), error = %_e, "Failed to write cache");
+                }
+                Ok(object)
+            }
+            Err(error) => match std::fs::read(&self.path) {
+                Ok(buf) => self.load_cache(buf, error),
+                Err(_) => Err(error),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<S> crate::source::AsyncConfigSource for CachedSource<S>
+where
+    S: crate::source::AsyncConfigSource + Send + Sync,
+{
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        use tokio::io::AsyncWriteExt;
+
+        // Errors are not `Send`, so they must not be held across an await point. The cache is read
+        // before loading the source, so that nothing is awaited after the source failed.
+        let cached = tokio::fs::read(&self.path).await;
+        let object = match self.source.load_async().await {
+            Ok(object) => object,
+            Err(error) => {
+                return match cached {
+                    Ok(buf) => self.load_cache(buf, error),
+                    Err(_) => Err(error),
+                }
+            }
+        };
+
+        let buf = Self::serialize(&object).ok();
+        if let Some(buf) = buf {
+            let tmp = self.tmp_path();
+            let options = tokio::fs::OpenOptions::from(Self::open_options());
+            let written = async {
+                let mut file = options.open(&tmp).await?;
+                file.write_all(&buf).await?;
+                file.flush().await?;
+                tokio::fs::rename(&tmp, &self.path).await
+            }
+            .await;
+            if let Err(_e) = written {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(path = %self.path.display(), error = %_e, "Failed to write cache");
+            }
+        }
+        Ok(object)
+    }
+}
+
+/// A [Value] as it is stored in the cache file
+///
+/// Floats are stored as strings, as JSON has no NaN and infinity.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CachedValue {
+    Null,
+    Bool(bool),
+    PosInt(u128),
+    NegInt(i128),
+    Float(#[serde(with = "as_string")] f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Datetime(#[serde(with = "as_string")] Datetime),
+    List(Vec<CachedValue>),
+    Map(BTreeMap<String, CachedValue>),
+}
+
+impl From<Value> for CachedValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => CachedValue::Null,
+            Value::Bool(b) => CachedValue::Bool(b),
+            Value::Number(Number::PosInt(u)) => CachedValue::PosInt(u),
+            Value::Number(Number::NegInt(i)) => CachedValue::NegInt(i),
+            Value::Number(Number::Float(f)) => CachedValue::Float(f),
+            Value::Str(s) => CachedValue::Str(s),
+            Value::Bytes(b) => CachedValue::Bytes(b),
+            Value::Datetime(d) => CachedValue::Datetime(d),
+            Value::List(l) => CachedValue::List(l.into_iter().map(CachedValue::from).collect()),
+            Value::Map(m) => CachedValue::Map(
+                m.into_iter()
+                    .map(|(k, v)| (k, CachedValue::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<CachedValue> for Value {
+    fn from(value: CachedValue) -> Self {
+        match value {
+            CachedValue::Null => Value::Null,
+            CachedValue::Bool(b) => Value::Bool(b),
+            CachedValue::PosInt(u) => Value::from(u),
+            CachedValue::NegInt(i) => Value::from(i),
+            CachedValue::Float(f) => Value::from(f),
+            CachedValue::Str(s) => Value::Str(s),
+            CachedValue::Bytes(b) => Value::Bytes(b),
+            CachedValue::Datetime(d) => Value::Datetime(d),
+            CachedValue::List(l) => Value::List(l.into_iter().map(Value::from).collect()),
+            CachedValue::Map(m) => {
+                Value::Map(m.into_iter().map(|(k, v)| (k, Value::from(v))).collect())
+            }
+        }
+    }
+}
+
+/// (De)serializes a value as its string representation
+mod as_string {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use serde::Deserialize;
+
+    pub(super) fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: serde::Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub(super) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    /// A source that fails to load once `available` is set to false
+    #[derive(Debug)]
+    struct Flaky {
+        value: Value,
+        available: AtomicBool,
+    }
+
+    impl ConfigSource for Flaky {
+        fn load(&self) -> Result<ConfigObject, SourceError> {
+            if self.available.load(Ordering::SeqCst) {
+                let desc = ConfigSourceDescription::Custom("remote".to_string());
+                Ok(ConfigObject::new(Box::new(self.value.clone()), desc))
+            } else {
+                Err(SourceError::Io(
+                    std::io::ErrorKind::ConnectionRefused.into(),
+                ))
+            }
+        }
+    }
+
+    #[test]
+    fn test_cached_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote.json");
+        let source = CachedSource::new(
+            Flaky {
+                value: Value::from(
+                    serde_json::json!({ "db": { "hosts": ["a", "b"], "port": 5432 } }),
+                ),
+                available: AtomicBool::new(false),
+            },
+            &path,
+        )
+        .unwrap();
+
+        // Nothing cached yet
+        assert!(std::matches!(source.load(), Err(SourceError::Io(_))));
+
+        source.source.available.store(true, Ordering::SeqCst);
+        let object = source.load().unwrap();
+        assert!(std::matches!(
+            object.description(),
+            ConfigSourceDescription::Custom(_)
+        ));
+
+        source.source.available.store(false, Ordering::SeqCst);
+        let object = source.load().unwrap();
+        match object.description() {
+            ConfigSourceDescription::Cached { path: p, .. } => assert_eq!(p, &path),
+            other => panic!("Unexpected description: {:?}", other),
+        }
+        let mut accessor = crate::ParsableAccessor::parse(&"db.hosts.1").unwrap();
+        let host = object.get(&mut accessor).unwrap().unwrap();
+        assert_eq!(host.as_str(), Some("b"));
+        let mut accessor = crate::ParsableAccessor::parse(&"db.port").unwrap();
+        let port = object.get(&mut accessor).unwrap().unwrap();
+        assert_eq!(port.as_u16(), Some(5432));
+    }
+
+    #[cfg(feature = "async")]
+    #[async_trait::async_trait]
+    impl crate::source::AsyncConfigSource for Flaky {
+        async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+            self.load()
+        }
+    }
+
+    #[test]
+    fn test_corrupt_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote.json");
+        let source = CachedSource::new(
+            Flaky {
+                value: Value::from_iter([("port", Value::from(5432))]),
+                available: AtomicBool::new(true),
+            },
+            &path,
+        )
+        .unwrap();
+
+        source.load().unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::write(&path, "{ \"map\": ").unwrap();
+        source.source.available.store(false, Ordering::SeqCst);
+        assert!(std::matches!(source.load(), Err(SourceError::Io(_))));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_cached_source_async() {
+        use crate::source::AsyncConfigSource;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote.json");
+        let source = CachedSource::new(
+            Flaky {
+                value: Value::from_iter([("port", Value::from(5432))]),
+                available: AtomicBool::new(true),
+            },
+            &path,
+        )
+        .unwrap();
+
+        source.load_async().await.unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        source.source.available.store(false, Ordering::SeqCst);
+        let object = source.load_async().await.unwrap();
+        assert!(std::matches!(
+            object.description(),
+            ConfigSourceDescription::Cached { .. }
+        ));
+
+        std::fs::write(&path, "{ \"map\": ").unwrap();
+        assert!(std::matches!(
+            source.load_async().await,
+            Err(SourceError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_cached_types() {
+        let value = Value::from_iter([
+            ("key", Value::Bytes(vec![0, 159, 255])),
+            ("big", Value::from(u128::MAX)),
+            ("small", Value::from(i128::MIN)),
+            ("ratio", Value::from(0.5)),
+            ("nan", Value::from(f64::NAN)),
+            ("list", Value::from(vec![Value::Null, Value::from(true)])),
+            (
+                "when",
+                Value::from("1979-05-27T07:32:00Z".parse::<Datetime>().unwrap()),
+            ),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let source = CachedSource::new(
+            Flaky {
+                value: value.clone(),
+                available: AtomicBool::new(true),
+            },
+            dir.path().join("remote.json"),
+        )
+        .unwrap();
+
+        source.load().unwrap();
+        source.source.available.store(false, Ordering::SeqCst);
+        let object = source.load().unwrap();
+        assert!(std::matches!(
+            object.description(),
+            ConfigSourceDescription::Cached { .. }
+        ));
+        assert_eq!(object.element().to_owned_value().unwrap(), value);
+    }
+}
@@ -0,0 +1,317 @@
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::fmt::Debug;
+use std::io::Read;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::FormatParser;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+/// A source that runs a program and parses what it prints to stdout
+///
+/// The program is run with the configured arguments and environment variables (in addition to the
+/// environment of the current process) each time the source is loaded. Its stdout is parsed with
+/// `P`.
+///
+/// If the program exits with a non-zero status, loading fails with
+/// [SourceError::CommandFailed], which carries the stderr output of the program.
+/// If a timeout is set and the program does not finish within it, the program is killed and
+/// loading fails with [SourceError::CommandTimeout].
+///
+/// The source is described by the program name only, as arguments often contain tokens or
+/// passwords. Use [CommandSource::describe_args] to include them.
+#[derive(Debug)]
+pub struct CommandSource<P>
+where
+    P: FormatParser + std::fmt::Debug,
+{
+    program: OsString,
+    args: Vec<OsString>,
+    env: Vec<(OsString, OsString)>,
+    timeout: Option<Duration>,
+    describe_args: bool,
+    _pd: std::marker::PhantomData<P>,
+}
+
+impl<P: FormatParser> CommandSource<P> {
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Result<Self, SourceError> {
+        Ok(CommandSource {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            env: Vec::new(),
+            timeout: None,
+            describe_args: false,
+            _pd: std::marker::PhantomData,
+        })
+    }
+
+    /// Add an argument that is passed to the program
+    pub fn arg<S: AsRef<OsStr>>(mut self, arg: S) -> Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Add multiple arguments that are passed to the program
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_os_string()));
+        self
+    }
+
+    /// Set an environment variable for the program
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(mut self, key: K, value: V) -> Self {
+        self.env
+            .push((key.as_ref().to_os_string(), value.as_ref().to_os_string()));
+        self
+    }
+
+    /// Kill the program and fail loading if it does not finish within `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Include the arguments in the description of the source
+    ///
+    /// The description shows up in errors and [ConfigView](crate::ConfigView)s, so only use this
+    /// if the arguments contain no secrets.
+    pub fn describe_args(mut self) -> Self {
+        self.describe_args = true;
+        self
+    }
+
+    fn program_name(&self) -> String {
+        self.program.to_string_lossy().to_string()
+    }
+
+    fn description(&self) -> ConfigSourceDescription {
+        let args = self.args.iter().filter(|_| self.describe_args);
+        let command = std::iter::once(&self.program)
+            .chain(args)
+            .map(|s| s.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        ConfigSourceDescription::Command(command)
+    }
+
+    fn check_status(
+        &self,
+        status: std::process::ExitStatus,
+        stderr: Vec<u8>,
+    ) -> Result<(), SourceError> {
+        if status.success() {
+            Ok(())
+        } else {
+            Err(SourceError::CommandFailed {
+                program: self.program_name(),
+                status,
+                stderr: String::from_utf8_lossy(&stderr).to_string(),
+            })
+        }
+    }
+}
+
+impl<P> ConfigSource for CommandSource<P>
+where
+    P: FormatParser + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        let mut child = std::process::Command::new(&self.program)
+            .args(&self.args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Read both pipes in the background, so the program cannot block on a full pipe while we
+        // wait for it to exit
+        fn read_pipe<R: Read + Send + 'static>(
+            pipe: Option<R>,
+        ) -> std::thread::JoinHandle<std::io::Result<Vec<u8>>> {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    pipe.read_to_end(&mut buf)?;
+                }
+                Ok(buf)
+            })
+        }
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+
+        let status = match self.timeout {
+            None => child.wait()?,
+            Some(timeout) => {
+                let deadline = Instant::now() + timeout;
+                loop {
+                    if let Some(status) = child.try_wait()? {
+                        break status;
+                    }
+
+                    if Instant::now() >= deadline {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Err(SourceError::CommandTimeout {
+                            program: self.program_name(),
+                            timeout,
+                        });
+                    }
+
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            }
+        };
+
+        let join = |handle: std::thread::JoinHandle<std::io::Result<Vec<u8>>>| {
+            handle
+                .join()
+                .unwrap_or_else(|_| Err(std::io::Error::from(std::io::ErrorKind::Other)))
+        };
+        let stdout = join(stdout)?;
+        let stderr = join(stderr)?;
+
+        self.check_status(status, stderr)?;
+        let (element, spans, comments) = P::parse_with_comments(stdout)?;
+        Ok(ConfigObject::new(Box::new(element), self.description())
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<P> crate::source::AsyncConfigSource for CommandSource<P>
+where
+    P: FormatParser + Send + Sync + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        let child = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let output = match self.timeout {
+            None => child.wait_with_output().await?,
+            Some(timeout) => tokio::time::timeout(timeout, child.wait_with_output())
+                .await
+                .map_err(|_| SourceError::CommandTimeout {
+                    program: self.program_name(),
+                    timeout,
+                })??,
+        };
+
+        self.check_status(output.status, output.stderr)?;
+        let (element, spans, comments) = P::parse_with_comments(output.stdout)?;
+        Ok(ConfigObject::new(Box::new(element), self.description())
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+}
+
+#[cfg(all(test, unix, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::source::JsonFormatParser;
+
+    #[test]
+    fn test_command_source() {
+        let source = CommandSource::<JsonFormatParser>::new("sh")
+            .unwrap()
+            .arg("-c")
+            .arg(r#"echo "{\"key\": \"$VALUE\"}""#)
+            .env("VALUE", "value");
+
+        let object = source.load().unwrap();
+        let mut accessor = crate::ParsableAccessor::parse(&"key").unwrap();
+        let value = object.get(&mut accessor).unwrap().unwrap();
+        assert_eq!(value.as_str(), Some("value"));
+    }
+
+    #[test]
+    fn test_command_source_description() {
+        let source = CommandSource::<JsonFormatParser>::new("echo")
+            .unwrap()
+            .args(["--token", "secret"]);
+        assert!(std::matches!(
+            source.description(),
+            ConfigSourceDescription::Command(c) if c == "echo"
+        ));
+
+        let source = source.describe_args();
+        assert!(std::matches!(
+            source.description(),
+            ConfigSourceDescription::Command(c) if c == "echo --token secret"
+        ));
+    }
+
+    #[test]
+    fn test_command_source_failure() {
+        let source = CommandSource::<JsonFormatParser>::new("sh")
+            .unwrap()
+            .args(["-c", "echo oops >&2; exit 3"]);
+
+        match source.load() {
+            Err(SourceError::CommandFailed { status, stderr, .. }) => {
+                assert_eq!(status.code(), Some(3));
+                assert_eq!(stderr, "oops\n");
+            }
+            other => panic!("Expected CommandFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_command_source_timeout() {
+        let source = CommandSource::<JsonFormatParser>::new("sleep")
+            .unwrap()
+            .arg("5")
+            .timeout(Duration::from_millis(50));
+
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::CommandTimeout { .. })
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_command_source_async() {
+        use crate::source::AsyncConfigSource;
+
+        let source = CommandSource::<JsonFormatParser>::new("echo")
+            .unwrap()
+            .arg(r#"{"key": "value"}"#);
+
+        let object = source.load_async().await.unwrap();
+        let mut accessor = crate::ParsableAccessor::parse(&"key").unwrap();
+        let value = object.get(&mut accessor).unwrap().unwrap();
+        assert_eq!(value.as_str(), Some("value"));
+
+        let source = CommandSource::<JsonFormatParser>::new("sleep")
+            .unwrap()
+            .arg("5")
+            .timeout(Duration::from_millis(50));
+
+        assert!(std::matches!(
+            source.load_async().await,
+            Err(SourceError::CommandTimeout { .. })
+        ));
+    }
+}
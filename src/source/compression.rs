@@ -0,0 +1,167 @@
+//! Decompressing the content of sources before parsing
+
+use super::SourceError;
+
+/// The default for the maximum size of decompressed content: 64 MiB
+pub(crate) const DEFAULT_MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The compression codec of the content of a source
+///
+/// Each codec is only available if the corresponding cargo feature (`gzip`, `zstd`, `xz`) is
+/// enabled. Selecting a codec explicitly without enabling its feature makes loading the source fail
+/// with [SourceError::UnsupportedCompression].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Compression {
+    /// Detect the codec from the magic bytes at the start of the content
+    ///
+    /// Content that is not compressed with one of the enabled codecs is used as-is.
+    #[default]
+    Auto,
+
+    /// The content is not compressed
+    None,
+
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+    const GZIP_MAGIC: &'static [u8] = &[0x1f, 0x8b];
+    const ZSTD_MAGIC: &'static [u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+    const XZ_MAGIC: &'static [u8] = &[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00];
+
+    fn detect(buf: &[u8]) -> Self {
+        if cfg!(feature = "gzip") && buf.starts_with(Self::GZIP_MAGIC) {
+            Compression::Gzip
+        } else if cfg!(feature = "zstd") && buf.starts_with(Self::ZSTD_MAGIC) {
+            Compression::Zstd
+        } else if cfg!(feature = "xz") && buf.starts_with(Self::XZ_MAGIC) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Decompress `buf`, failing if the decompressed content is larger than `max_size` bytes
+    #[cfg_attr(
+        not(any(feature = "gzip", feature = "zstd", feature = "xz")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn decompress(self, buf: Vec<u8>, max_size: u64) -> Result<Vec<u8>, SourceError> {
+        let compression = match self {
+            Compression::Auto => Self::detect(&buf),
+            other => other,
+        };
+
+        match compression {
+            Compression::Auto | Compression::None => Ok(buf),
+
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                read_limited(flate2::read::MultiGzDecoder::new(&buf[..]), max_size)
+            }
+
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => read_limited(zstd::Decoder::new(&buf[..])?, max_size),
+
+            #[cfg(feature = "xz")]
+            Compression::Xz => read_limited(xz2::read::XzDecoder::new(&buf[..]), max_size),
+
+            #[allow(unreachable_patterns)]
+            other => Err(SourceError::UnsupportedCompression(other)),
+        }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "xz"))]
+fn read_limited<R: std::io::Read>(reader: R, max_size: u64) -> Result<Vec<u8>, SourceError> {
+    use std::io::Read;
+
+    let mut buf = Vec::new();
+    reader
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut buf)?;
+
+    if buf.len() as u64 > max_size {
+        Err(SourceError::DecompressedSizeLimit(max_size))
+    } else {
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &[u8] = br#"{"key": "value"}"#;
+
+    #[test]
+    fn test_uncompressed_content_is_unchanged() {
+        let buf = Compression::Auto.decompress(CONTENT.to_vec(), 1).unwrap();
+        assert_eq!(buf, CONTENT);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(CONTENT).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        for compression in [Compression::Auto, Compression::Gzip] {
+            let buf = compression.decompress(compressed.clone(), 1024).unwrap();
+            assert_eq!(buf, CONTENT);
+        }
+        assert!(std::matches!(
+            Compression::Gzip.decompress(compressed, 4),
+            Err(SourceError::DecompressedSizeLimit(4))
+        ));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        let compressed = zstd::encode_all(CONTENT, 0).unwrap();
+
+        for compression in [Compression::Auto, Compression::Zstd] {
+            let buf = compression.decompress(compressed.clone(), 1024).unwrap();
+            assert_eq!(buf, CONTENT);
+        }
+        assert!(std::matches!(
+            Compression::Zstd.decompress(compressed, 4),
+            Err(SourceError::DecompressedSizeLimit(4))
+        ));
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn test_xz() {
+        use std::io::Write;
+
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(CONTENT).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        for compression in [Compression::Auto, Compression::Xz] {
+            let buf = compression.decompress(compressed.clone(), 1024).unwrap();
+            assert_eq!(buf, CONTENT);
+        }
+        assert!(std::matches!(
+            Compression::Xz.decompress(compressed, 4),
+            Err(SourceError::DecompressedSizeLimit(4))
+        ));
+    }
+
+    #[cfg(not(feature = "xz"))]
+    #[test]
+    fn test_unsupported_compression() {
+        assert!(std::matches!(
+            Compression::Xz.decompress(CONTENT.to_vec(), 1024),
+            Err(SourceError::UnsupportedCompression(Compression::Xz))
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+//! Consul KV configuration source
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use base64::Engine;
+use reqwest::StatusCode;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::parse_boxed;
+use crate::source::format::FormatParser;
+use crate::source::kv::strip_key_prefix;
+use crate::source::kv::KeyValueTree;
+use crate::source::kv::ValueParser;
+
+use super::SourceError;
+
+const CONSUL_INDEX_HEADER: &str = "X-Consul-Index";
+const CONSUL_TOKEN_HEADER: &str = "X-Consul-Token";
+
+/// One entry of the response of Consul's `/v1/kv` endpoint
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KvEntry {
+    key: String,
+    value: Option<String>,
+}
+
+/// A source that reads all keys below a prefix from the Consul KV store
+///
+/// Keys are split at `/` after the prefix is removed, and each part becomes one level of nested
+/// maps. With the prefix `myapp/`, the key `myapp/database/port` becomes accessible as
+/// `database.port`. Folder entries (keys ending with `/`) are skipped.
+///
+/// Values are parsed as booleans or numbers if possible and used as strings otherwise. Use
+/// [ConsulSource::format] to parse each value with a [FormatParser] instead.
+///
+/// The [ConfigSourceDescription] of each value is the URI of its key in the KV API.
+///
+/// # Reloading
+///
+/// [ConsulSource::wait_for_change] performs a blocking query, which returns as soon as a key below
+/// the prefix changed since the source was loaded last.
+#[derive(Debug)]
+pub struct ConsulSource {
+    client: reqwest::Client,
+    address: url::Url,
+    prefix: String,
+    token: Option<String>,
+    parser: ValueParser,
+    index: Mutex<Option<u64>>,
+}
+
+impl ConsulSource {
+    /// Create a source for all keys below `prefix` from the Consul agent at `address`
+    ///
+    /// The address is the base URL of the agent, for example `http://127.0.0.1:8500`.
+    pub fn new<Pr: Into<String>>(address: url::Url, prefix: Pr) -> Result<Self, SourceError> {
+        Ok(ConsulSource {
+            client: reqwest::Client::new(),
+            address,
+            prefix: prefix.into(),
+            token: None,
+            parser: ValueParser::Scalar,
+            index: Mutex::new(None),
+        })
+    }
+
+    /// Parse each value with `P` instead of as a scalar
+    pub fn format<P>(mut self) -> Self
+    where
+        P: FormatParser,
+        <P as FormatParser>::Output: 'static,
+    {
+        self.parser = ValueParser::Format(parse_boxed::<P>);
+        self
+    }
+
+    /// Authenticate requests with an ACL token
+    pub fn token<T: Into<String>>(mut self, token: T) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Use a custom client, for example to configure timeouts or certificates
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Wait until a key below the prefix changes
+    ///
+    /// Performs a blocking query with the index of the last load. Consul answers as soon as the
+    /// index changes or after `wait` elapsed, whatever comes first. Returns whether the index
+    /// changed, that is, whether the configuration should be reloaded.
+    ///
+    /// If the source was not loaded yet, this returns `true` immediately.
+    pub async fn wait_for_change(&self, wait: Duration) -> Result<bool, SourceError> {
+        let last_index = match self.last_index() {
+            Some(index) => index,
+            None => return Ok(true),
+        };
+
+        let wait = format!("{}ms", wait.as_millis());
+        let request = self.request(&[("index", &last_index.to_string()), ("wait", &wait)])?;
+        let response = request.send().await?;
+        self.check_status(response.status())?;
+
+        Ok(consul_index(&response) != Some(last_index))
+    }
+
+    /// The URL of `key` in the KV store, with each segment of the key percent-encoded
+    ///
+    /// The API path is appended to the path of the address, which may or may not end with a `/`.
+    fn kv_url(&self, key: &str) -> Result<url::Url, SourceError> {
+        let mut url = self.address.clone();
+        url.path_segments_mut()
+            .map_err(|_| {
+                SourceError::Custom(format!("Invalid Consul address '{}'", self.address).into())
+            })?
+            .pop_if_empty()
+            .extend(["v1", "kv"])
+            .extend(key.split('/'));
+        Ok(url)
+    }
+
+    fn request(&self, query: &[(&str, &str)]) -> Result<reqwest::RequestBuilder, SourceError> {
+        let mut request = self
+            .client
+            .get(self.kv_url(&self.prefix)?)
+            .query(&[("recurse", "true")])
+            .query(query);
+
+        if let Some(token) = self.token.as_ref() {
+            request = request.header(CONSUL_TOKEN_HEADER, token);
+        }
+
+        Ok(request)
+    }
+
+    fn check_status(&self, status: StatusCode) -> Result<(), SourceError> {
+        if status.is_success() || status == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(SourceError::HttpStatus {
+                url: self.kv_url(&self.prefix)?,
+                status: status.as_u16(),
+            })
+        }
+    }
+
+    fn last_index(&self) -> Option<u64> {
+        *self
+            .index
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn consul_index(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONSUL_INDEX_HEADER)
+        .and_then(|index| index.to_str().ok())
+        .and_then(|index| index.parse().ok())
+}
+
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for ConsulSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        let request = self.request(&[])?;
+        let response = request.send().await?;
+        self.check_status(response.status())?;
+
+        let index = consul_index(&response);
+        // Consul answers with 404 if there is no key below the prefix
+        let entries = if response.status() == StatusCode::NOT_FOUND {
+            Vec::new()
+        } else {
+            serde_json::from_slice::<Vec<KvEntry>>(&response.bytes().await?)?
+        };
+
+        let mut tree = KeyValueTree::new();
+        for entry in entries {
+            let value = match entry.value {
+                Some(value) if !entry.key.ends_with('/') => value,
+                _ => continue,
+            };
+
+            let value = base64::engine::general_purpose::STANDARD.decode(value)?;
+            let path = match strip_key_prefix(&entry.key, &self.prefix) {
+                Some(rest) => rest.split('/').filter(|part| !part.is_empty()),
+                None => continue,
+            };
+            let desc = ConfigSourceDescription::Uri(self.kv_url(&entry.key)?);
+
+            tree.insert_with_description(path, self.parser.parse(&entry.key, value)?, Some(desc))?;
+        }
+
+        *self
+            .index
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = index;
+
+        let desc = ConfigSourceDescription::Uri(self.kv_url(&self.prefix)?);
+        Ok(ConfigObject::new(Box::new(tree), desc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kv_url() {
+        let address = url::Url::parse("http://127.0.0.1:8500").unwrap();
+        let source = ConsulSource::new(address, "app/").unwrap();
+
+        let url = source.kv_url(&source.prefix).unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8500/v1/kv/app/");
+
+        let url = source.kv_url("app/db name/pass?word#1%").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://127.0.0.1:8500/v1/kv/app/db%20name/pass%3Fword%231%25"
+        );
+
+        for address in ["http://127.0.0.1/consul", "http://127.0.0.1/consul/"] {
+            let source = ConsulSource::new(url::Url::parse(address).unwrap(), "app").unwrap();
+            let url = source.kv_url(&source.prefix).unwrap();
+            assert_eq!(url.as_str(), "http://127.0.0.1/consul/v1/kv/app");
+        }
+    }
+}
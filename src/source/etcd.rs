@@ -0,0 +1,131 @@
+//! etcd configuration source
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::parse_boxed;
+use crate::source::format::FormatParser;
+use crate::source::kv::strip_key_prefix;
+use crate::source::kv::KeyValueTree;
+use crate::source::kv::ValueParser;
+
+use super::SourceError;
+
+/// A source that reads all keys below a prefix from etcd
+///
+/// Keys are split at `/` after the prefix is removed, and each part becomes one level of nested
+/// maps. With the prefix `/myapp/`, the key `/myapp/database/port` becomes accessible as
+/// `database.port`.
+///
+/// Values are parsed as booleans or numbers if possible and used as strings otherwise. Use
+/// [EtcdSource::format] to parse each value with a [FormatParser] instead.
+///
+/// # Reloading
+///
+/// [EtcdSource::watch] returns a stream that yields an item each time a key below the prefix
+/// changes, which can be used to trigger reloading the configuration.
+pub struct EtcdSource {
+    client: etcd_client::Client,
+    prefix: String,
+    parser: ValueParser,
+}
+
+impl std::fmt::Debug for EtcdSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EtcdSource")
+            .field("prefix", &self.prefix)
+            .field("parser", &self.parser)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EtcdSource {
+    /// Connect to etcd and create a source for all keys below `prefix`
+    pub async fn connect<E, S, Pr>(endpoints: S, prefix: Pr) -> Result<Self, SourceError>
+    where
+        E: AsRef<str>,
+        S: AsRef<[E]>,
+        Pr: Into<String>,
+    {
+        let client = etcd_client::Client::connect(endpoints, None).await?;
+        Self::from_client(client, prefix)
+    }
+
+    /// Create a source for all keys below `prefix` from an already connected client
+    pub fn from_client<Pr: Into<String>>(
+        client: etcd_client::Client,
+        prefix: Pr,
+    ) -> Result<Self, SourceError> {
+        Ok(EtcdSource {
+            client,
+            prefix: prefix.into(),
+            parser: ValueParser::Scalar,
+        })
+    }
+
+    /// Parse each value with `P` instead of as a scalar
+    pub fn format<P>(mut self) -> Self
+    where
+        P: FormatParser,
+        <P as FormatParser>::Output: 'static,
+    {
+        self.parser = ValueParser::Format(parse_boxed::<P>);
+        self
+    }
+
+    /// Watch all keys below the prefix for changes
+    ///
+    /// The returned stream yields `Ok(())` for each batch of changes etcd reports. It ends when
+    /// the watch is cancelled by etcd.
+    pub async fn watch(&self) -> Result<BoxStream<'static, Result<(), SourceError>>, SourceError> {
+        let options = etcd_client::WatchOptions::new().with_prefix();
+        let (watcher, stream) = self
+            .client
+            .clone()
+            .watch(self.prefix.as_str(), Some(options))
+            .await?;
+
+        let changes = futures::stream::unfold((watcher, stream), |(watcher, mut stream)| async {
+            loop {
+                match stream.message().await {
+                    Ok(Some(response)) if response.canceled() => return None,
+                    Ok(Some(response)) if response.events().is_empty() => continue,
+                    Ok(Some(_)) => return Some((Ok(()), (watcher, stream))),
+                    Ok(None) => return None,
+                    Err(e) => return Some((Err(SourceError::from(e)), (watcher, stream))),
+                }
+            }
+        });
+
+        Ok(changes.boxed())
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for EtcdSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        let options = etcd_client::GetOptions::new().with_prefix();
+        let response = self
+            .client
+            .clone()
+            .get(self.prefix.as_str(), Some(options))
+            .await?;
+
+        let mut tree = KeyValueTree::new();
+        for kv in response.kvs() {
+            let key = kv.key_str()?;
+            let path = match strip_key_prefix(key, &self.prefix) {
+                Some(rest) => rest.split('/').filter(|part| !part.is_empty()),
+                None => continue,
+            };
+
+            let value = self.parser.parse(key, kv.value().to_vec())?;
+            tree.insert(path, value)?;
+        }
+
+        let desc = ConfigSourceDescription::Custom(format!("etcd:{}", self.prefix));
+        Ok(ConfigObject::new(Box::new(tree), desc))
+    }
+}
@@ -4,6 +4,8 @@ use std::path::PathBuf;
 
 use crate::description::ConfigSourceDescription;
 use crate::object::ConfigObject;
+use crate::source::compression::Compression;
+use crate::source::compression::DEFAULT_MAX_DECOMPRESSED_SIZE;
 use crate::source::format::FormatParser;
 use crate::ConfigSource;
 
@@ -15,6 +17,8 @@ where
     P: FormatParser + std::fmt::Debug,
 {
     path: PathBuf,
+    compression: Compression,
+    max_decompressed_size: u64,
     _pd: std::marker::PhantomData<P>,
 }
 
@@ -22,9 +26,31 @@ impl<P: FormatParser> FileSource<P> {
     pub fn new<Pa: AsRef<Path>>(source: Pa) -> Result<Self, SourceError> {
         Ok(FileSource {
             path: source.as_ref().to_path_buf(),
+            compression: Compression::Auto,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
             _pd: std::marker::PhantomData,
         })
     }
+
+    /// Set the compression codec of the file
+    ///
+    /// By default, the codec is detected from the content of the file.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Fail loading if the decompressed content of the file is larger than `max_size` bytes
+    ///
+    /// Defaults to 64 MiB.
+    pub fn max_decompressed_size(mut self, max_size: u64) -> Self {
+        self.max_decompressed_size = max_size;
+        self
+    }
+
+    fn decompress(&self, buf: Vec<u8>) -> Result<Vec<u8>, SourceError> {
+        self.compression.decompress(buf, self.max_decompressed_size)
+    }
 }
 
 impl<P> ConfigSource for FileSource<P>
@@ -33,11 +59,13 @@ where
     <P as FormatParser>::Output: 'static,
 {
     fn load(&self) -> Result<ConfigObject, SourceError> {
-        let buf = std::fs::read(&self.path)?;
-        let element = P::parse(buf)?;
+        let buf = self.decompress(std::fs::read(&self.path)?)?;
+        let (element, spans, comments) = P::parse_with_comments(buf)?;
 
-        let desc = ConfigSourceDescription::Custom("String".to_string());
-        Ok(ConfigObject::new(Box::new(element), desc))
+        let desc = ConfigSourceDescription::Path(self.path.clone());
+        Ok(ConfigObject::new(Box::new(element), desc)
+            .with_spans(spans)
+            .with_comments(comments))
     }
 }
 
@@ -49,10 +77,33 @@ where
     <P as FormatParser>::Output: 'static,
 {
     async fn load_async(&self) -> Result<ConfigObject, SourceError> {
-        let buf = tokio::fs::read(&self.path).await?;
-        let element = P::parse(buf)?;
+        let buf = self.decompress(tokio::fs::read(&self.path).await?)?;
+        let (element, spans, comments) = P::parse_with_comments(buf)?;
+
+        let desc = ConfigSourceDescription::Path(self.path.clone());
+        Ok(ConfigObject::new(Box::new(element), desc)
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::source::JsonFormatParser;
+
+    #[test]
+    fn test_file_source_description() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), r#"{ "key": "value" }"#).unwrap();
 
-        let desc = ConfigSourceDescription::Custom("String".to_string());
-        Ok(ConfigObject::new(Box::new(element), desc))
+        let object = FileSource::<JsonFormatParser>::new(file.path())
+            .unwrap()
+            .load()
+            .unwrap();
+        match object.description() {
+            ConfigSourceDescription::Path(path) => assert_eq!(path, file.path()),
+            other => panic!("Unexpected description: {:?}", other),
+        }
     }
 }
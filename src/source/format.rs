@@ -1,10 +1,60 @@
 use super::SourceError;
+use crate::comment::Comments;
 use crate::element::ConfigElement;
+use crate::span::Spans;
 
 pub trait FormatParser: std::fmt::Debug {
     type Output: ConfigElement + std::fmt::Debug + Sized;
 
     fn parse(buffer: Vec<u8>) -> Result<Self::Output, SourceError>;
+
+    /// Parse `buffer` and record the [Span](crate::Span) of each value
+    ///
+    /// The default implementation records no spans.
+    fn parse_with_spans(buffer: Vec<u8>) -> Result<(Self::Output, Spans), SourceError> {
+        Self::parse(buffer).map(|output| (output, Spans::new()))
+    }
+
+    /// Parse `buffer` and record the [Span](crate::Span) and [Comment](crate::Comment) of each
+    /// value
+    ///
+    /// The default implementation records the spans of [FormatParser::parse_with_spans] and no
+    /// comments.
+    fn parse_with_comments(
+        buffer: Vec<u8>,
+    ) -> Result<(Self::Output, Spans, Comments), SourceError> {
+        Self::parse_with_spans(buffer).map(|(output, spans)| (output, spans, Comments::new()))
+    }
+}
+
+/// A type-erased [FormatParser::parse], for sources that select their parser at runtime
+pub(crate) type ParseFn = fn(Vec<u8>) -> Result<Box<dyn ConfigElement>, SourceError>;
+
+pub(crate) fn parse_boxed<P>(buffer: Vec<u8>) -> Result<Box<dyn ConfigElement>, SourceError>
+where
+    P: FormatParser,
+    <P as FormatParser>::Output: 'static,
+{
+    P::parse(buffer).map(|element| Box::new(element) as Box<dyn ConfigElement>)
+}
+
+/// A type-erased [FormatParser::parse_with_comments], for sources that select the parser of
+/// their documents at runtime
+#[cfg(any(feature = "http", feature = "http-blocking"))]
+pub(crate) type ParseDocumentFn =
+    fn(Vec<u8>) -> Result<(Box<dyn ConfigElement>, Spans, Comments), SourceError>;
+
+#[cfg(any(feature = "http", feature = "http-blocking"))]
+pub(crate) fn parse_document_boxed<P>(
+    buffer: Vec<u8>,
+) -> Result<(Box<dyn ConfigElement>, Spans, Comments), SourceError>
+where
+    P: FormatParser,
+    <P as FormatParser>::Output: 'static,
+{
+    P::parse_with_comments(buffer).map(|(element, spans, comments)| {
+        (Box::new(element) as Box<dyn ConfigElement>, spans, comments)
+    })
 }
 
 #[cfg(feature = "json")]
@@ -18,6 +68,16 @@ impl FormatParser for JsonFormatParser {
     fn parse(buffer: Vec<u8>) -> Result<Self::Output, SourceError> {
         serde_json::from_slice(&buffer).map_err(SourceError::JsonParserError)
     }
+
+    fn parse_with_spans(buffer: Vec<u8>) -> Result<(Self::Output, Spans), SourceError> {
+        let document = match std::str::from_utf8(&buffer) {
+            Ok(document) => document,
+            // Not valid JSON either, let serde_json report the error
+            Err(_) => return Self::parse(buffer).map(|value| (value, Spans::new())),
+        };
+        let value = serde_json::from_str(document).map_err(SourceError::JsonParserError)?;
+        Ok((value, crate::element::json::spans(document)))
+    }
 }
 
 #[cfg(feature = "toml")]
@@ -32,4 +92,23 @@ impl FormatParser for TomlFormatParser {
         let buffer = String::from_utf8(buffer).map_err(SourceError::from)?;
         toml::from_str(&buffer).map_err(SourceError::TomlParserError)
     }
+
+    fn parse_with_spans(buffer: Vec<u8>) -> Result<(Self::Output, Spans), SourceError> {
+        let buffer = String::from_utf8(buffer).map_err(SourceError::from)?;
+        let document =
+            toml_edit::ImDocument::parse(buffer.as_str()).map_err(toml_edit::de::Error::from)?;
+        let spans = crate::element::toml::spans(&document);
+        Ok((crate::element::toml::value(document)?, spans))
+    }
+
+    fn parse_with_comments(
+        buffer: Vec<u8>,
+    ) -> Result<(Self::Output, Spans, Comments), SourceError> {
+        let buffer = String::from_utf8(buffer).map_err(SourceError::from)?;
+        let document =
+            toml_edit::ImDocument::parse(buffer.as_str()).map_err(toml_edit::de::Error::from)?;
+        let spans = crate::element::toml::spans(&document);
+        let comments = crate::element::toml::comments(&document);
+        Ok((crate::element::toml::value(document)?, spans, comments))
+    }
 }
@@ -0,0 +1,196 @@
+//! Git repository configuration source
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::comment::Comments;
+use crate::description::ConfigSourceDescription;
+use crate::element::ConfigElement;
+use crate::element::ConfigElementListType;
+use crate::element::ConfigElementMapType;
+use crate::object::ConfigObject;
+use crate::source::format::FormatParser;
+use crate::span::Spans;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+/// A source that reads files from a commit of a local git repository
+///
+/// The reference (a branch, a tag, a commit id or anything else `git rev-parse` understands) is
+/// resolved to a commit, and the files are read from the tree of that commit, without checking
+/// out a working tree. Bare repositories work as well.
+///
+/// Each file added with [GitSource::path] is parsed with `P`. If more than one file is read, their
+/// top-level maps are merged, with later files taking precedence. Nested maps are not merged.
+///
+/// The [ConfigSourceDescription] of the source is [ConfigSourceDescription::Git], which contains
+/// the id of the commit the files were read from.
+///
+/// # Reloading
+///
+/// The reference is resolved again each time the source is loaded, so reloading a configuration
+/// picks up updates of a branch.
+///
+/// # Note
+///
+/// Loading is synchronous, also when the source is loaded as
+/// [AsyncConfigSource](crate::AsyncConfigSource).
+#[derive(Debug)]
+pub struct GitSource<P>
+where
+    P: FormatParser + std::fmt::Debug,
+{
+    repository: PathBuf,
+    reference: String,
+    paths: Vec<PathBuf>,
+    _pd: std::marker::PhantomData<P>,
+}
+
+impl<P: FormatParser> GitSource<P> {
+    /// Create a source that reads from `reference` in the repository at `repository`
+    pub fn new<R, Re>(repository: R, reference: Re) -> Result<Self, SourceError>
+    where
+        R: AsRef<Path>,
+        Re: Into<String>,
+    {
+        Ok(GitSource {
+            repository: repository.as_ref().to_path_buf(),
+            reference: reference.into(),
+            paths: Vec::new(),
+            _pd: std::marker::PhantomData,
+        })
+    }
+
+    /// Read the file at `path`, relative to the root of the repository
+    pub fn path<Pa: AsRef<Path>>(mut self, path: Pa) -> Self {
+        self.paths.push(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl<P> ConfigSource for GitSource<P>
+where
+    P: FormatParser + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        let repository = git2::Repository::open(&self.repository)?;
+        let commit = repository
+            .revparse_single(&self.reference)?
+            .peel_to_commit()?;
+        let tree = commit.tree()?;
+
+        let mut documents = Vec::with_capacity(self.paths.len());
+        for path in self.paths.iter() {
+            let blob = tree
+                .get_path(path)?
+                .to_object(&repository)?
+                .peel_to_blob()?;
+            documents.push(P::parse_with_comments(blob.content().to_vec())?);
+        }
+
+        let (element, spans, comments) = if documents.len() == 1 {
+            let (element, spans, comments) = documents.remove(0);
+            (Box::new(element) as Box<dyn ConfigElement>, spans, comments)
+        } else {
+            merge(documents)
+        };
+
+        let desc = ConfigSourceDescription::Git {
+            repository: self.repository.clone(),
+            reference: self.reference.clone(),
+            commit: commit.id().to_string(),
+            paths: self.paths.clone(),
+        };
+        Ok(ConfigObject::new(element, desc)
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<P> crate::source::AsyncConfigSource for GitSource<P>
+where
+    P: FormatParser + Send + Sync + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        self.load()
+    }
+}
+
+/// Merge the top-level maps of several parsed files
+///
+/// The spans and comments of each key are taken from the file the key is looked up from.
+fn merge<T>(documents: Vec<(T, Spans, Comments)>) -> (Box<dyn ConfigElement>, Spans, Comments)
+where
+    T: ConfigElement + 'static,
+{
+    let mut merged = Vec::with_capacity(documents.len());
+    let mut spans = Spans::new();
+    let mut comments = Comments::new();
+    for (element, mut document_spans, mut document_comments) in documents {
+        if let Some(map) = element.as_map() {
+            for key in map.keys_iter() {
+                *spans.key(key) = std::mem::take(document_spans.key(key));
+                *comments.key(key) = std::mem::take(document_comments.key(key));
+            }
+        }
+        merged.push(Box::new(element) as Box<dyn ConfigElement>);
+    }
+
+    (Box::new(MergedDocuments(merged)), spans, comments)
+}
+
+/// Several parsed files, with their top-level maps merged
+///
+/// Looking up a key returns the value from the last file that contains the key.
+#[derive(Debug)]
+struct MergedDocuments(Vec<Box<dyn ConfigElement>>);
+
+impl ConfigElementMapType for MergedDocuments {
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        self.0
+            .iter()
+            .rev()
+            .find_map(|document| document.as_map()?.get(key))
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        let mut seen = HashSet::new();
+        Box::new(
+            self.0
+                .iter()
+                .filter_map(|d| d.as_map())
+                .flat_map(|document| document.keys_iter())
+                .filter(move |key| seen.insert(*key))
+                .filter_map(|key| Some((key, ConfigElementMapType::get(self, key)?))),
+        )
+    }
+}
+
+impl ConfigElement for MergedDocuments {
+    fn as_bool(&self) -> Option<bool> {
+        None
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        None
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        Some(self)
+    }
+
+    fn is_null(&self) -> bool {
+        false
+    }
+}
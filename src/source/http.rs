@@ -0,0 +1,265 @@
+//! HTTP(S) configuration sources
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderValue;
+use reqwest::StatusCode;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::parse_document_boxed;
+use crate::source::format::FormatParser;
+use crate::source::format::ParseDocumentFn;
+
+use super::SourceError;
+
+/// Select a parser for a `Content-Type` header value
+fn parser_for_content_type(content_type: Option<&str>) -> Result<ParseDocumentFn, SourceError> {
+    let mime = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase());
+
+    match mime.as_deref() {
+        #[cfg(feature = "json")]
+        Some(m) if m == "application/json" || m.ends_with("+json") => {
+            Ok(parse_document_boxed::<crate::source::JsonFormatParser>)
+        }
+
+        #[cfg(feature = "toml")]
+        Some("application/toml") | Some("text/toml") | Some("text/x-toml") => {
+            Ok(parse_document_boxed::<crate::source::TomlFormatParser>)
+        }
+
+        _ => Err(SourceError::ContentType(content_type.map(String::from))),
+    }
+}
+
+/// The last successful response, used for conditional requests
+#[derive(Debug)]
+struct CachedResponse {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+/// State that is shared between the async and the blocking HTTP source
+#[derive(Debug)]
+struct HttpState {
+    url: url::Url,
+    format: Option<ParseDocumentFn>,
+    cache: Mutex<Option<Arc<CachedResponse>>>,
+}
+
+impl HttpState {
+    fn new(url: url::Url) -> Self {
+        HttpState {
+            url,
+            format: None,
+            cache: Mutex::new(None),
+        }
+    }
+
+    fn cached(&self) -> Option<Arc<CachedResponse>> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Headers that turn the request into a conditional request, if there was a response before
+    fn conditional_headers(&self, cached: Option<&CachedResponse>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(cached) = cached {
+            if let Some(etag) = cached.etag.as_ref() {
+                headers.insert(reqwest::header::IF_NONE_MATCH, etag.clone());
+            }
+            if let Some(last_modified) = cached.last_modified.as_ref() {
+                headers.insert(reqwest::header::IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+        headers
+    }
+
+    fn check_status(&self, status: StatusCode) -> Result<(), SourceError> {
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SourceError::HttpStatus {
+                url: self.url.clone(),
+                status: status.as_u16(),
+            })
+        }
+    }
+
+    fn store(&self, headers: &HeaderMap, body: Vec<u8>) -> Arc<CachedResponse> {
+        let response = Arc::new(CachedResponse {
+            etag: headers.get(reqwest::header::ETAG).cloned(),
+            last_modified: headers.get(reqwest::header::LAST_MODIFIED).cloned(),
+            content_type: headers
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|ct| ct.to_str().ok())
+                .map(String::from),
+            body,
+        });
+
+        *self
+            .cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(response.clone());
+        response
+    }
+
+    fn parse(&self, response: &CachedResponse) -> Result<ConfigObject, SourceError> {
+        let parse = match self.format {
+            Some(parse) => parse,
+            None => parser_for_content_type(response.content_type.as_deref())?,
+        };
+
+        let (element, spans, comments) = parse(response.body.clone())?;
+        let desc = ConfigSourceDescription::Uri(self.url.clone());
+        Ok(ConfigObject::new(element, desc)
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+}
+
+/// A source that fetches configuration from an HTTP(S) URL
+///
+/// By default, the format of the response body is selected by its `Content-Type` header. Use
+/// [HttpSource::format] to parse the body with a specific [FormatParser] instead.
+///
+/// # Reloading
+///
+/// The `ETag` and `Last-Modified` headers of the last successful response are remembered and sent
+/// as `If-None-Match` and `If-Modified-Since` headers when the source is loaded again. If the
+/// server responds with `304 Not Modified`, the previously fetched body is parsed again instead
+/// of being transferred.
+///
+/// # Note
+///
+/// See [BlockingHttpSource] for a variant of this source that does not need an async runtime.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct HttpSource {
+    client: reqwest::Client,
+    state: HttpState,
+}
+
+#[cfg(feature = "http")]
+impl HttpSource {
+    pub fn new(url: url::Url) -> Result<Self, SourceError> {
+        Ok(HttpSource {
+            client: reqwest::Client::new(),
+            state: HttpState::new(url),
+        })
+    }
+
+    /// Parse the response body with `P`, regardless of its `Content-Type`
+    pub fn format<P>(mut self) -> Self
+    where
+        P: FormatParser,
+        <P as FormatParser>::Output: 'static,
+    {
+        self.state.format = Some(parse_document_boxed::<P>);
+        self
+    }
+
+    /// Use a custom client, for example to configure timeouts or certificates
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for HttpSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        let cached = self.state.cached();
+        let response = self
+            .client
+            .get(self.state.url.clone())
+            .headers(self.state.conditional_headers(cached.as_deref()))
+            .send()
+            .await?;
+
+        let response = match cached {
+            Some(cached) if response.status() == StatusCode::NOT_MODIFIED => cached,
+            _ => {
+                self.state.check_status(response.status())?;
+                let headers = response.headers().clone();
+                let body = response.bytes().await?.to_vec();
+                self.state.store(&headers, body)
+            }
+        };
+
+        self.state.parse(&response)
+    }
+}
+
+/// A source that fetches configuration from an HTTP(S) URL using a blocking client
+///
+/// Works exactly like [HttpSource], but implements [ConfigSource](crate::ConfigSource).
+///
+/// # Warning
+///
+/// The blocking client must not be used from within an async runtime.
+#[cfg(feature = "http-blocking")]
+#[derive(Debug)]
+pub struct BlockingHttpSource {
+    client: reqwest::blocking::Client,
+    state: HttpState,
+}
+
+#[cfg(feature = "http-blocking")]
+impl BlockingHttpSource {
+    pub fn new(url: url::Url) -> Result<Self, SourceError> {
+        Ok(BlockingHttpSource {
+            client: reqwest::blocking::Client::new(),
+            state: HttpState::new(url),
+        })
+    }
+
+    /// Parse the response body with `P`, regardless of its `Content-Type`
+    pub fn format<P>(mut self) -> Self
+    where
+        P: FormatParser,
+        <P as FormatParser>::Output: 'static,
+    {
+        self.state.format = Some(parse_document_boxed::<P>);
+        self
+    }
+
+    /// Use a custom client, for example to configure timeouts or certificates
+    pub fn with_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+#[cfg(feature = "http-blocking")]
+impl crate::ConfigSource for BlockingHttpSource {
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        let cached = self.state.cached();
+        let response = self
+            .client
+            .get(self.state.url.clone())
+            .headers(self.state.conditional_headers(cached.as_deref()))
+            .send()?;
+
+        let response = match cached {
+            Some(cached) if response.status() == StatusCode::NOT_MODIFIED => cached,
+            _ => {
+                self.state.check_status(response.status())?;
+                let headers = response.headers().clone();
+                let body = response.bytes()?.to_vec();
+                self.state.store(&headers, body)
+            }
+        };
+
+        self.state.parse(&response)
+    }
+}
@@ -0,0 +1,367 @@
+//! Building configuration trees from flat key-value stores
+//!
+//! Key-value stores (etcd, Consul, ...) hold flat keys like `a/b/c`. The types in this module turn
+//! such keys into a tree of nested maps, where each value is either parsed as a scalar or with a
+//! [FormatParser](crate::FormatParser).
+
+use std::collections::BTreeMap;
+
+use crate::description::ConfigSourceDescription;
+use crate::element::ConfigElement;
+use crate::element::ConfigElementCustomType;
+use crate::element::ConfigElementListType;
+use crate::element::ConfigElementMapType;
+use crate::element::Datetime;
+use crate::element::Number;
+#[cfg(any(feature = "redis", feature = "consul", feature = "etcd"))]
+use crate::source::format::ParseFn;
+use crate::source::SourceError;
+
+/// How the values of a key-value store are turned into [ConfigElement]s
+#[cfg(any(feature = "redis", feature = "consul", feature = "etcd"))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ValueParser {
+    /// Parse values as booleans or numbers if possible, use them as strings otherwise
+    Scalar,
+
+    /// Parse values with a format parser
+    Format(ParseFn),
+}
+
+#[cfg(any(feature = "redis", feature = "consul", feature = "etcd"))]
+impl ValueParser {
+    /// Parse the `value` of `key`
+    pub(crate) fn parse(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<Box<dyn ConfigElement>, SourceError> {
+        match self {
+            ValueParser::Scalar => {
+                let s = String::from_utf8(value).map_err(|_| SourceError::InvalidValue {
+                    key: key.to_string(),
+                    reason: "not valid UTF-8".to_string(),
+                })?;
+                Ok(Box::new(Scalar::parse(s)))
+            }
+            ValueParser::Format(parse) => parse(value),
+        }
+    }
+}
+
+/// The part of `key` below `prefix`, or `None` if `key` is not below `prefix`
+///
+/// The prefix has to end at a `/` of the key, so that the prefix `app` does not select the key
+/// `application/port`. The key that equals the prefix has no path below it and is skipped as
+/// well.
+#[cfg(any(feature = "consul", feature = "etcd"))]
+pub(crate) fn strip_key_prefix<'a>(key: &'a str, prefix: &str) -> Option<&'a str> {
+    let prefix = prefix.trim_end_matches('/');
+    let rest = key.strip_prefix(prefix)?;
+    if !prefix.is_empty() && !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+    if rest.split('/').all(str::is_empty) {
+        return None;
+    }
+    Some(rest)
+}
+
+/// A scalar value from a key-value store
+///
+/// Booleans and numbers parsed from a string keep that string, so they can still be used as
+/// strings (e.g. `"0644"`).
+#[cfg(any(
+    feature = "sqlite",
+    feature = "redis",
+    feature = "consul",
+    feature = "etcd"
+))]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Scalar {
+    #[cfg(feature = "sqlite")]
+    Null,
+    Bool(bool, Option<String>),
+    Number(Number, Option<String>),
+    Str(String),
+    #[cfg(feature = "sqlite")]
+    Bytes(Vec<u8>),
+}
+
+#[cfg(any(
+    feature = "sqlite",
+    feature = "redis",
+    feature = "consul",
+    feature = "etcd"
+))]
+impl Scalar {
+    #[cfg(any(feature = "redis", feature = "consul", feature = "etcd"))]
+    pub(crate) fn parse(s: String) -> Self {
+        if let Ok(b) = s.parse::<bool>() {
+            Scalar::Bool(b, Some(s))
+        } else if let Ok(n) = s.parse::<Number>() {
+            Scalar::Number(n, Some(s))
+        } else {
+            Scalar::Str(s)
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "sqlite",
+    feature = "redis",
+    feature = "consul",
+    feature = "etcd"
+))]
+impl ConfigElement for Scalar {
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Scalar::Bool(b, _) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<Number> {
+        match self {
+            Scalar::Number(n, _) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Scalar::Str(s) => Some(s),
+            Scalar::Bool(_, Some(s)) | Scalar::Number(_, Some(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            #[cfg(feature = "sqlite")]
+            Scalar::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        None
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        None
+    }
+
+    fn is_null(&self) -> bool {
+        match self {
+            #[cfg(feature = "sqlite")]
+            Scalar::Null => true,
+            _ => false,
+        }
+    }
+}
+
+/// A tree of nested maps, with arbitrary [ConfigElement]s as leaves
+#[derive(Debug)]
+pub(crate) enum KeyValueTree {
+    Map(KeyValueMap),
+    Value(Box<dyn ConfigElement>, Option<ConfigSourceDescription>),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KeyValueMap(BTreeMap<String, KeyValueTree>);
+
+impl KeyValueTree {
+    pub(crate) fn new() -> Self {
+        KeyValueTree::Map(KeyValueMap::default())
+    }
+
+    /// Insert a value at a path of keys, creating intermediate maps as required
+    ///
+    /// Fails with [SourceError::KeyConflict] if the path runs through a value or if the path
+    /// points to a map that already contains values.
+    #[cfg(any(feature = "sqlite", feature = "redis", feature = "etcd"))]
+    pub(crate) fn insert<'a, I>(
+        &mut self,
+        path: I,
+        value: Box<dyn ConfigElement>,
+    ) -> Result<(), SourceError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.insert_with_description(path, value, None)
+    }
+
+    /// Like [KeyValueTree::insert], but records where the value came from
+    ///
+    /// The description is returned from [ConfigElement::source_description] for the value.
+    pub(crate) fn insert_with_description<'a, I>(
+        &mut self,
+        path: I,
+        value: Box<dyn ConfigElement>,
+        desc: Option<ConfigSourceDescription>,
+    ) -> Result<(), SourceError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut full_path = Vec::new();
+        let mut current = self;
+        for key in path {
+            full_path.push(key);
+            current = match current {
+                KeyValueTree::Map(map) => map
+                    .0
+                    .entry(key.to_string())
+                    .or_insert_with(KeyValueTree::new),
+                KeyValueTree::Value(..) => {
+                    return Err(SourceError::KeyConflict(full_path.join(".")));
+                }
+            };
+        }
+
+        match current {
+            KeyValueTree::Map(map) if map.0.is_empty() => {
+                *current = KeyValueTree::Value(value, desc);
+                Ok(())
+            }
+            _ => Err(SourceError::KeyConflict(full_path.join("."))),
+        }
+    }
+}
+
+impl ConfigElementMapType for KeyValueMap {
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        self.0.get(key).map(|t| t as &dyn ConfigElement)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        Box::new(
+            self.0
+                .iter()
+                .map(|(k, t)| (k.as_str(), t as &dyn ConfigElement)),
+        )
+    }
+}
+
+macro_rules! delegate_to_value {
+    ($($fun:ident -> $t:ty),* $(,)?) => {
+        $(
+            fn $fun(&self) -> Option<$t> {
+                match self {
+                    KeyValueTree::Value(v, _) => v.$fun(),
+                    KeyValueTree::Map(_) => None,
+                }
+            }
+        )*
+    };
+}
+
+impl ConfigElement for KeyValueTree {
+    delegate_to_value! {
+        as_bool -> bool,
+        as_number -> Number,
+        as_str -> &str,
+        as_bytes -> &[u8],
+        as_list -> &dyn ConfigElementListType,
+        as_datetime -> Datetime,
+        as_custom -> &dyn ConfigElementCustomType,
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        match self {
+            KeyValueTree::Value(v, _) => v.as_map(),
+            KeyValueTree::Map(m) => Some(m),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        match self {
+            KeyValueTree::Value(v, _) => v.is_null(),
+            KeyValueTree::Map(_) => false,
+        }
+    }
+
+    fn source_description(&self) -> Option<&ConfigSourceDescription> {
+        match self {
+            KeyValueTree::Value(v, desc) => desc.as_ref().or_else(|| v.source_description()),
+            KeyValueTree::Map(_) => None,
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "redis", feature = "consul", feature = "etcd")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar_numbers() {
+        let max = Scalar::parse(u64::MAX.to_string());
+        assert_eq!(max.as_u64(), Some(u64::MAX));
+        assert_eq!(max.as_i64(), None);
+
+        assert_eq!(Scalar::parse("255".to_string()).as_u8(), Some(255));
+        assert_eq!(Scalar::parse("0".to_string()).as_u8(), Some(0));
+        assert_eq!(Scalar::parse("-129".to_string()).as_i8(), None);
+        assert_eq!(Scalar::parse("1.5".to_string()).as_f32(), Some(1.5));
+    }
+
+    #[test]
+    fn test_scalar_keeps_string() {
+        use crate::FromConfigElement;
+
+        for s in ["0644", "0012345", "123", "true", "nan", "inf"] {
+            let scalar = Scalar::parse(s.to_string());
+            assert_eq!(scalar.as_str(), Some(s));
+            assert_eq!(String::from_config_element(&scalar).unwrap(), s);
+        }
+        assert_eq!(Scalar::parse("0644".to_string()).as_u16(), Some(644));
+        assert_eq!(Scalar::parse("true".to_string()).as_bool(), Some(true));
+    }
+
+    #[cfg(any(feature = "consul", feature = "etcd"))]
+    #[test]
+    fn test_strip_key_prefix() {
+        assert_eq!(strip_key_prefix("/app/db/port", "/app"), Some("/db/port"));
+        assert_eq!(strip_key_prefix("/app/db/port", "/app/"), Some("/db/port"));
+        assert_eq!(strip_key_prefix("/application/port", "/app"), None);
+        assert_eq!(strip_key_prefix("/app", "/app"), None);
+        assert_eq!(strip_key_prefix("/app/", "/app"), None);
+        assert_eq!(strip_key_prefix("port", ""), Some("port"));
+    }
+
+    #[cfg(any(feature = "redis", feature = "etcd"))]
+    #[test]
+    fn test_key_value_tree() {
+        let mut tree = KeyValueTree::new();
+        for (path, value) in [("a/b/c", "1"), ("a/b/d", "true"), ("a/e", "text")] {
+            let value = ValueParser::Scalar
+                .parse(path, value.as_bytes().to_vec())
+                .unwrap();
+            tree.insert(path.split('/'), value).unwrap();
+        }
+
+        let mut accessor = crate::ParsableAccessor::parse(&"a.b.c").unwrap();
+        assert_eq!(
+            tree.access(&mut accessor).unwrap().unwrap().as_u8(),
+            Some(1)
+        );
+
+        let mut accessor = crate::ParsableAccessor::parse(&"a.b.d").unwrap();
+        assert_eq!(
+            tree.access(&mut accessor).unwrap().unwrap().as_bool(),
+            Some(true)
+        );
+
+        let mut accessor = crate::ParsableAccessor::parse(&"a.e").unwrap();
+        assert_eq!(
+            tree.access(&mut accessor).unwrap().unwrap().as_str(),
+            Some("text")
+        );
+
+        let value = Box::new(Scalar::Number(Number::from(2), None));
+        assert!(std::matches!(
+            tree.insert("a/b/c/d".split('/'), value),
+            Err(SourceError::KeyConflict(_))
+        ));
+    }
+}
@@ -1,16 +1,79 @@
 use crate::object::ConfigObject;
 
+#[cfg(feature = "age")]
+mod age;
 #[cfg(feature = "async")]
 mod async_source;
+#[cfg(feature = "json")]
+mod cache;
+mod command;
+mod compression;
+#[cfg(feature = "consul")]
+mod consul;
+#[cfg(feature = "etcd")]
+mod etcd;
 mod file;
 mod format;
+#[cfg(feature = "git")]
+mod git;
+#[cfg(any(feature = "http", feature = "http-blocking"))]
+mod http;
+mod kv;
+#[cfg(feature = "redis")]
+mod redis;
+mod secret_file;
+#[cfg(feature = "sops")]
+mod sops;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stdin;
 mod string;
+mod systemd;
+#[cfg(feature = "vault")]
+mod vault;
 
+#[cfg(feature = "age")]
+pub use crate::source::age::AgeCipher;
+#[cfg(feature = "age")]
+pub use crate::source::age::AgeKey;
+#[cfg(feature = "age")]
+pub use crate::source::age::AgeSource;
 #[cfg(feature = "async")]
 pub use crate::source::async_source::AsyncConfigSource;
+#[cfg(feature = "json")]
+pub use crate::source::cache::CachedSource;
+pub use crate::source::command::CommandSource;
+pub use crate::source::compression::Compression;
+#[cfg(feature = "consul")]
+pub use crate::source::consul::ConsulSource;
+#[cfg(feature = "etcd")]
+pub use crate::source::etcd::EtcdSource;
 pub use crate::source::file::FileSource;
 pub use crate::source::format::FormatParser;
+#[cfg(feature = "git")]
+pub use crate::source::git::GitSource;
+#[cfg(feature = "http-blocking")]
+pub use crate::source::http::BlockingHttpSource;
+#[cfg(feature = "http")]
+pub use crate::source::http::HttpSource;
+#[cfg(feature = "redis")]
+pub use crate::source::redis::RedisSource;
+pub use crate::source::secret_file::SecretFileSource;
+#[cfg(feature = "sops")]
+pub use crate::source::sops::SopsFormat;
+#[cfg(feature = "sops")]
+pub use crate::source::sops::SopsSource;
+#[cfg(feature = "sqlite")]
+pub use crate::source::sqlite::SqliteSource;
+pub use crate::source::stdin::StdinSource;
 pub use crate::source::string::StringSource;
+pub use crate::source::systemd::SystemdCredentialsSource;
+#[cfg(feature = "vault")]
+pub use crate::source::vault::VaultAuth;
+#[cfg(feature = "vault")]
+pub use crate::source::vault::VaultLease;
+#[cfg(feature = "vault")]
+pub use crate::source::vault::VaultSource;
 
 #[cfg(feature = "json")]
 pub use crate::source::format::JsonFormatParser;
@@ -37,19 +100,157 @@ pub enum SourceError {
     #[error("IO Error")]
     Io(#[from] std::io::Error),
 
+    #[error("Command '{program}' failed with {status}: {stderr}")]
+    CommandFailed {
+        program: String,
+        status: std::process::ExitStatus,
+        stderr: String,
+    },
+
+    #[error("Command '{program}' did not finish within {timeout:?}")]
+    CommandTimeout {
+        program: String,
+        timeout: std::time::Duration,
+    },
+
+    #[error("Environment variable '{0}' is not set")]
+    EnvVarNotSet(String),
+
+    #[error("Secret file '{path}' is larger than {max_size} bytes")]
+    SecretFileTooLarge {
+        path: std::path::PathBuf,
+        max_size: u64,
+    },
+
+    #[error("Compression {0:?} is not supported, the corresponding feature is not enabled")]
+    UnsupportedCompression(compression::Compression),
+
+    #[error("Decompressed content is larger than {0} bytes")]
+    DecompressedSizeLimit(u64),
+
+    #[error("Configuration is invalid")]
+    Validation(#[source] Box<dyn std::error::Error>),
+
+    #[error("Conflicting keys at '{0}'")]
+    KeyConflict(String),
+
+    #[error("No cipher for encrypted values with scheme '{0}'")]
+    UnknownCipher(String),
+
+    #[error("Failed to decrypt value at '{key}'")]
+    DecryptValue {
+        key: String,
+        #[source]
+        source: Box<SourceError>,
+    },
+
+    #[cfg(feature = "json")]
+    #[error("Configuration cannot be cached")]
+    Uncacheable(#[source] Box<crate::config::FromConfigElementError>),
+
+    #[cfg(feature = "etcd")]
+    #[error("etcd error")]
+    Etcd(#[source] Box<etcd_client::Error>),
+
+    #[cfg(feature = "git")]
+    #[error("Git error")]
+    Git(#[from] git2::Error),
+
+    #[cfg(any(
+        feature = "http",
+        feature = "http-blocking",
+        feature = "consul",
+        feature = "vault"
+    ))]
+    #[error("HTTP error")]
+    Http(#[from] reqwest::Error),
+
+    #[cfg(any(
+        feature = "http",
+        feature = "http-blocking",
+        feature = "consul",
+        feature = "vault"
+    ))]
+    #[error("HTTP request to '{url}' failed with status {status}")]
+    HttpStatus { url: url::Url, status: u16 },
+
+    #[cfg(any(feature = "http", feature = "http-blocking"))]
+    #[error("No format parser for content type {0:?}")]
+    ContentType(Option<String>),
+
+    #[cfg(feature = "age")]
+    #[error("age decryption failed")]
+    AgeDecrypt(#[from] ::age::DecryptError),
+
+    #[cfg(feature = "age")]
+    #[error("No age identity found in {0}")]
+    AgeNoIdentity(String),
+
+    #[cfg(feature = "sops")]
+    #[error("Invalid SOPS metadata: {0}")]
+    SopsMetadata(String),
+
+    #[cfg(feature = "sops")]
+    #[error("None of the age identities can decrypt the SOPS data key")]
+    SopsNoMatchingKey,
+
+    #[cfg(feature = "sops")]
+    #[error("Failed to decrypt SOPS value at '{0}'")]
+    SopsDecrypt(String),
+
+    #[cfg(feature = "sops")]
+    #[error("The SOPS MAC does not match the values of the file")]
+    SopsMacMismatch,
+
+    #[cfg(any(feature = "age", feature = "consul"))]
+    #[error("Base64 decoding error")]
+    Base64(#[from] base64::DecodeError),
+
+    #[cfg(feature = "redis")]
+    #[error("Redis error")]
+    Redis(#[from] ::redis::RedisError),
+
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[cfg(any(
+        feature = "sqlite",
+        feature = "redis",
+        feature = "consul",
+        feature = "etcd"
+    ))]
+    #[error("Invalid value for key '{key}': {reason}")]
+    InvalidValue { key: String, reason: String },
+
     #[cfg(feature = "json")]
     #[error("JSON Parser error")]
     JsonParserError(#[from] serde_json::Error),
 
+    #[cfg(feature = "sops")]
+    #[error("YAML Parser error")]
+    YamlParserError(#[from] serde_yaml::Error),
+
     #[cfg(feature = "toml")]
     #[error("TOML Parser error")]
     TomlParserError(#[from] toml::de::Error),
 
     #[cfg(feature = "toml")]
+    #[error("TOML Parser error")]
+    TomlDocumentError(#[from] toml_edit::de::Error),
+
+    #[cfg(any(feature = "toml", feature = "sops"))]
     #[error("UTF8 Error")]
     Utf8(#[from] std::string::FromUtf8Error),
 }
 
+#[cfg(feature = "etcd")]
+impl From<etcd_client::Error> for SourceError {
+    fn from(e: etcd_client::Error) -> Self {
+        SourceError::Etcd(Box::new(e))
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test_source {
     use crate::description::ConfigSourceDescription;
@@ -0,0 +1,185 @@
+//! Redis configuration source
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::parse_boxed;
+use crate::source::format::FormatParser;
+use crate::source::kv::KeyValueTree;
+use crate::source::kv::ValueParser;
+
+use super::SourceError;
+
+/// What a [RedisSource] reads
+#[derive(Debug, Clone)]
+enum Selection {
+    /// All fields of one hash
+    Hash(String),
+
+    /// All string keys matching a glob-style pattern
+    Pattern(String),
+}
+
+/// A source that reads a Redis hash or all keys matching a pattern
+///
+/// * With [RedisSource::hash], each field of the hash becomes one value. Field names are split at
+///   `.` by default, so the field `database.port` becomes accessible as `database.port`.
+/// * With [RedisSource::pattern], each string key matching the pattern becomes one value. The
+///   literal prefix of the pattern (everything before the first wildcard) is removed and keys are
+///   split at `:` by default. With the pattern `myapp:*`, the key `myapp:database:port` becomes
+///   accessible as `database.port`.
+///
+/// Use [RedisSource::separator] to split at another character.
+///
+/// Values are parsed as booleans or numbers if possible and used as strings otherwise. Use
+/// [RedisSource::format] to parse each value with a [FormatParser] instead.
+///
+/// # Reloading
+///
+/// [RedisSource::watch] subscribes to keyspace notifications for the hash or the pattern and
+/// returns a stream that yields an item for each change. Redis only publishes these notifications
+/// if they are enabled with the `notify-keyspace-events` setting, for example
+/// `CONFIG SET notify-keyspace-events KA`.
+#[derive(Debug)]
+pub struct RedisSource {
+    client: redis::Client,
+    selection: Selection,
+    separator: char,
+    parser: ValueParser,
+}
+
+impl RedisSource {
+    /// Create a source for all fields of the hash at `key`, on the server at `address`
+    ///
+    /// The address is a Redis URL, for example `redis://127.0.0.1:6379/0`.
+    pub fn hash<K: Into<String>>(address: url::Url, key: K) -> Result<Self, SourceError> {
+        Self::new(address, Selection::Hash(key.into()), '.')
+    }
+
+    /// Create a source for all string keys matching `pattern`, on the server at `address`
+    ///
+    /// The pattern uses the glob-style syntax of the Redis `SCAN` command. Keys of other types than
+    /// strings are skipped, which requires Redis 6 or newer.
+    pub fn pattern<Pa: Into<String>>(address: url::Url, pattern: Pa) -> Result<Self, SourceError> {
+        Self::new(address, Selection::Pattern(pattern.into()), ':')
+    }
+
+    fn new(address: url::Url, selection: Selection, separator: char) -> Result<Self, SourceError> {
+        Ok(RedisSource {
+            client: redis::Client::open(address)?,
+            selection,
+            separator,
+            parser: ValueParser::Scalar,
+        })
+    }
+
+    /// Split keys (or hash fields) at `separator`
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Parse each value with `P` instead of as a scalar
+    pub fn format<P>(mut self) -> Self
+    where
+        P: FormatParser,
+        <P as FormatParser>::Output: 'static,
+    {
+        self.parser = ValueParser::Format(parse_boxed::<P>);
+        self
+    }
+
+    /// Watch the hash or the keys matching the pattern for changes
+    ///
+    /// The returned stream yields `Ok(())` for each keyspace notification. It ends when the
+    /// connection to Redis is closed.
+    pub async fn watch(&self) -> Result<BoxStream<'static, Result<(), SourceError>>, SourceError> {
+        let db = self.client.get_connection_info().redis.db;
+        let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+        match &self.selection {
+            Selection::Hash(key) => {
+                pubsub
+                    .subscribe(format!("__keyspace@{}__:{}", db, key))
+                    .await?
+            }
+            Selection::Pattern(pattern) => {
+                pubsub
+                    .psubscribe(format!("__keyspace@{}__:{}", db, pattern))
+                    .await?
+            }
+        }
+
+        Ok(pubsub.into_on_message().map(|_| Ok(())).boxed())
+    }
+
+    /// Read the raw key-value pairs, with the keys relative to the selection
+    async fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, SourceError> {
+        let mut connection = self.client.get_async_connection().await?;
+        match &self.selection {
+            Selection::Hash(key) => Ok(redis::cmd("HGETALL")
+                .arg(key)
+                .query_async(&mut connection)
+                .await?),
+
+            Selection::Pattern(pattern) => {
+                let mut keys = Vec::new();
+                {
+                    let mut scan = redis::cmd("SCAN");
+                    scan.cursor_arg(0)
+                        .arg("MATCH")
+                        .arg(pattern)
+                        .arg("TYPE")
+                        .arg("string");
+                    let mut iter = scan.iter_async::<String>(&mut connection).await?;
+                    while let Some(key) = iter.next_item().await {
+                        keys.push(key);
+                    }
+                }
+                keys.sort();
+                keys.dedup();
+                if keys.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+                    .arg(&keys)
+                    .query_async(&mut connection)
+                    .await?;
+
+                let prefix_len = pattern.find(['*', '?', '[', '\\']).unwrap_or(pattern.len());
+                let prefix = &pattern[..prefix_len];
+                Ok(keys
+                    .into_iter()
+                    .zip(values)
+                    .filter_map(|(key, value)| {
+                        let key = key.strip_prefix(prefix).map(str::to_string).unwrap_or(key);
+                        value.map(|value| (key, value))
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    fn description(&self) -> ConfigSourceDescription {
+        let selection = match &self.selection {
+            Selection::Hash(key) => key,
+            Selection::Pattern(pattern) => pattern,
+        };
+        ConfigSourceDescription::Custom(format!("redis:{}", selection))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for RedisSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        let mut tree = KeyValueTree::new();
+        for (key, value) in self.entries().await? {
+            let path = key.split(self.separator).filter(|part| !part.is_empty());
+            tree.insert(path, self.parser.parse(&key, value)?)?;
+        }
+
+        Ok(ConfigObject::new(Box::new(tree), self.description()))
+    }
+}
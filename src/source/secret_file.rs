@@ -0,0 +1,303 @@
+//! Resolving `_file` keys into the content of the referenced files
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::description::ConfigSourceDescription;
+use crate::element::ConfigElement;
+use crate::element::ConfigElementListType;
+use crate::element::ConfigElementMapType;
+use crate::element::Shared;
+use crate::element::Value;
+use crate::object::ConfigObject;
+use crate::source::kv::KeyValueTree;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+const FILE_SUFFIX: &str = "_file";
+const DEFAULT_MAX_SIZE: u64 = 64 * 1024;
+
+/// A source that resolves `_file` keys of another source into the content of the referenced files
+///
+/// This implements the convention of Docker secrets: for each key ending with `_file` (or
+/// `_FILE`, matched case-insensitively) whose value is a string, the file at that path is read and
+/// its content is made available at the key without the suffix. With an inner source containing
+/// `database.password_file = "/run/secrets/db"`, the content of `/run/secrets/db` becomes
+/// accessible as `database.password`. For a layer of environment variables,
+/// `DB_PASSWORD_FILE=/run/secrets/db` becomes `DB_PASSWORD`.
+///
+/// Files are read each time the source is loaded. Trailing newlines are removed from the content,
+/// unless disabled with [SecretFileSource::trim_newlines]. Files larger than
+/// [SecretFileSource::max_size] (64 KiB by default) are rejected.
+///
+/// It is an error if the inner source contains both a `_file` key and the key without the suffix.
+/// `_file` keys inside of lists are not resolved.
+///
+/// The [ConfigSourceDescription] of each resolved value is the path of the secret file.
+#[derive(Debug)]
+pub struct SecretFileSource<S> {
+    source: S,
+    max_size: u64,
+    trim_newlines: bool,
+}
+
+impl<S> SecretFileSource<S> {
+    /// Resolve the `_file` keys of `source`
+    pub fn new(source: S) -> Result<Self, SourceError> {
+        Ok(SecretFileSource {
+            source,
+            max_size: DEFAULT_MAX_SIZE,
+            trim_newlines: true,
+        })
+    }
+
+    /// Reject secret files larger than `max_size` bytes
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Whether to remove trailing newlines from the content of secret files
+    pub fn trim_newlines(mut self, trim_newlines: bool) -> Self {
+        self.trim_newlines = trim_newlines;
+        self
+    }
+
+    fn resolve(&self, object: ConfigObject) -> Result<ConfigObject, SourceError> {
+        object.try_map_element(|element| {
+            let root: Arc<dyn ConfigElement> = Arc::from(element);
+            Ok(
+                match SecretFiles::resolve(self, &root, &*root, Vec::new())? {
+                    Some(resolved) => Box::new(resolved),
+                    None => Box::new(Shared(root)),
+                },
+            )
+        })
+    }
+
+    fn read(&self, path: PathBuf) -> Result<KeyValueTree, SourceError> {
+        let mut buf = Vec::new();
+        std::fs::File::open(&path)?
+            .take(self.max_size.saturating_add(1))
+            .read_to_end(&mut buf)?;
+        if buf.len() as u64 > self.max_size {
+            return Err(SourceError::SecretFileTooLarge {
+                path,
+                max_size: self.max_size,
+            });
+        }
+
+        let mut content = String::from_utf8(buf).map_err(|e| SourceError::Custom(Box::new(e)))?;
+        if self.trim_newlines {
+            let len = content.trim_end_matches(['\r', '\n']).len();
+            content.truncate(len);
+        }
+
+        let desc = ConfigSourceDescription::Path(path);
+        Ok(KeyValueTree::Value(
+            Box::new(Value::Str(content)),
+            Some(desc),
+        ))
+    }
+}
+
+impl<S: ConfigSource> ConfigSource for SecretFileSource<S> {
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        self.resolve(self.source.load()?)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<S> crate::source::AsyncConfigSource for SecretFileSource<S>
+where
+    S: crate::source::AsyncConfigSource + Send + Sync,
+{
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        self.resolve(self.source.load_async().await?)
+    }
+}
+
+/// A map of the inner source that contains resolved secrets, directly or further down
+///
+/// The map is identified by its path from the root of the inner source, as the map itself cannot
+/// be borrowed next to the root.
+#[derive(Debug)]
+struct SecretFiles {
+    root: Arc<dyn ConfigElement>,
+    path: Vec<String>,
+    secrets: BTreeMap<String, KeyValueTree>,
+    children: BTreeMap<String, SecretFiles>,
+}
+
+impl SecretFiles {
+    /// Resolve the secrets in `element`, which is found at `path` in `root`
+    ///
+    /// Returns `None` if there are no `_file` keys in `element`.
+    fn resolve<S>(
+        source: &SecretFileSource<S>,
+        root: &Arc<dyn ConfigElement>,
+        element: &dyn ConfigElement,
+        path: Vec<String>,
+    ) -> Result<Option<Self>, SourceError> {
+        let map = match element.as_map() {
+            Some(map) => map,
+            None => return Ok(None),
+        };
+
+        let mut secrets = BTreeMap::new();
+        let mut children = BTreeMap::new();
+        for (key, value) in map.entries() {
+            let split = key.len().saturating_sub(FILE_SUFFIX.len());
+            let is_file_key = split > 0
+                && key.is_char_boundary(split)
+                && key[split..].eq_ignore_ascii_case(FILE_SUFFIX);
+            if let (true, Some(file)) = (is_file_key, value.as_str()) {
+                let secret_key = key[..split].to_string();
+                if map.contains_key(&secret_key) {
+                    let mut full_path = path.clone();
+                    full_path.push(secret_key);
+                    return Err(SourceError::KeyConflict(full_path.join(".")));
+                }
+
+                let secret = source.read(PathBuf::from(file))?;
+                secrets.insert(secret_key, secret);
+            } else {
+                let mut child_path = path.clone();
+                child_path.push(key.to_string());
+                if let Some(child) = Self::resolve(source, root, value, child_path)? {
+                    children.insert(key.to_string(), child);
+                }
+            }
+        }
+
+        if secrets.is_empty() && children.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SecretFiles {
+            root: root.clone(),
+            path,
+            secrets,
+            children,
+        }))
+    }
+
+    /// The map of the inner source this map overlays
+    fn inner(&self) -> Option<&dyn ConfigElementMapType> {
+        let mut element = &*self.root;
+        for key in self.path.iter() {
+            element = element.as_map()?.get(key)?;
+        }
+        element.as_map()
+    }
+}
+
+impl ConfigElementMapType for SecretFiles {
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        if let Some(secret) = self.secrets.get(key) {
+            return Some(secret);
+        }
+        if let Some(child) = self.children.get(key) {
+            return Some(child);
+        }
+        self.inner()?.get(key)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        let inner = self
+            .inner()
+            .into_iter()
+            .flat_map(|inner| inner.entries())
+            .map(|(key, value)| match self.children.get(key) {
+                Some(child) => (key, child as &dyn ConfigElement),
+                None => (key, value),
+            });
+        let secrets = self
+            .secrets
+            .iter()
+            .map(|(key, secret)| (key.as_str(), secret as &dyn ConfigElement));
+        Box::new(inner.chain(secrets))
+    }
+}
+
+impl ConfigElement for SecretFiles {
+    fn as_bool(&self) -> Option<bool> {
+        None
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        None
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        Some(self)
+    }
+
+    fn is_null(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::source::JsonFormatParser;
+    use crate::StringSource;
+
+    fn source(json: String) -> SecretFileSource<StringSource<JsonFormatParser>> {
+        SecretFileSource::new(StringSource::new(json).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_secret_file_source() {
+        let secret = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(secret.path(), "hunter2\n").unwrap();
+        let path = secret.path().display().to_string();
+
+        let json = serde_json::json!({
+            "database": { "user": "app", "password_file": path },
+            "DB_PASSWORD_FILE": path,
+        });
+        let object = source(json.to_string()).load().unwrap();
+
+        for key in ["database.password", "DB_PASSWORD"] {
+            let mut accessor = crate::ParsableAccessor::parse(&key).unwrap();
+            let view = object.get_with_description(&mut accessor).unwrap().unwrap();
+            assert_eq!(view.value().as_str(), Some("hunter2"));
+            match view.description() {
+                ConfigSourceDescription::Path(p) => assert_eq!(p, secret.path()),
+                other => panic!("Unexpected description: {:?}", other),
+            }
+        }
+
+        let mut accessor = crate::ParsableAccessor::parse(&"database.user").unwrap();
+        let user = object.get(&mut accessor).unwrap().unwrap();
+        assert_eq!(user.as_str(), Some("app"));
+    }
+
+    #[test]
+    fn test_secret_file_source_errors() {
+        let secret = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(secret.path(), "0123456789").unwrap();
+        let path = secret.path().display().to_string();
+
+        let json = serde_json::json!({ "password_file": path });
+        let err = source(json.to_string()).max_size(4).load().unwrap_err();
+        assert!(std::matches!(
+            err,
+            SourceError::SecretFileTooLarge { max_size: 4, .. }
+        ));
+
+        let json = serde_json::json!({ "password": "x", "password_file": path });
+        let err = source(json.to_string()).load().unwrap_err();
+        assert!(std::matches!(err, SourceError::KeyConflict(key) if key == "password"));
+    }
+}
@@ -0,0 +1,648 @@
+//! SOPS-encrypted configuration files
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::path::PathBuf;
+
+use aes_gcm::aead::consts::U32;
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::KeyInit;
+use aes_gcm::aead::Payload;
+use aes_gcm::aes::Aes256;
+use base64::Engine;
+use sha2::Digest;
+use sha2::Sha512;
+
+use crate::comment::Comments;
+use crate::description::ConfigSourceDescription;
+use crate::element::Number;
+use crate::element::Value;
+use crate::object::ConfigObject;
+use crate::source::age::AgeKey;
+use crate::span::Spans;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+/// SOPS uses AES-256-GCM with 256 bit nonces
+type SopsCipher = aes_gcm::AesGcm<Aes256, U32>;
+
+const METADATA_KEY: &str = "sops";
+const ENCRYPTED_PREFIX: &str = "ENC[AES256_GCM,";
+
+/// The field of the map that the TOML deserializer passes datetimes as
+#[cfg(feature = "toml")]
+const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+/// The format of a SOPS-encrypted file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SopsFormat {
+    Json,
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl SopsFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(SopsFormat::Json),
+            "yaml" | "yml" => Some(SopsFormat::Yaml),
+            #[cfg(feature = "toml")]
+            "toml" => Some(SopsFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Parse the document, with the spans and comments the format records
+    fn parse(self, buf: Vec<u8>) -> Result<(Tree, Spans, Comments), SourceError> {
+        match self {
+            SopsFormat::Json => {
+                let document = String::from_utf8(buf).map_err(SourceError::from)?;
+                let tree = serde_json::from_str(&document)?;
+                Ok((
+                    tree,
+                    crate::element::json::spans(&document),
+                    Comments::new(),
+                ))
+            }
+            SopsFormat::Yaml => Ok((serde_yaml::from_slice(&buf)?, Spans::new(), Comments::new())),
+            #[cfg(feature = "toml")]
+            SopsFormat::Toml => {
+                let document = String::from_utf8(buf).map_err(SourceError::from)?;
+                let document = toml_edit::ImDocument::parse(document.as_str())
+                    .map_err(toml_edit::de::Error::from)?;
+                let spans = crate::element::toml::spans(&document);
+                let comments = crate::element::toml::comments(&document);
+                let tree =
+                    serde::Deserialize::deserialize(toml_edit::de::Deserializer::from(document))?;
+                Ok((tree, spans, comments))
+            }
+        }
+    }
+}
+
+/// A source that reads a file encrypted with [SOPS](https://github.com/getsops/sops)
+///
+/// In SOPS files, keys stay visible and only values are encrypted. The data key that encrypts the
+/// values is itself encrypted for one or more age recipients and stored in the `sops` metadata of
+/// the file. The data key is decrypted with the identities from an [AgeKey], everything else
+/// happens locally as well.
+///
+/// The format (JSON, YAML or TOML) is detected from the file extension, use
+/// [SopsSource::format] to set it explicitly. The `sops` metadata is not part of the
+/// configuration.
+///
+/// Each value is authenticated together with its position in the file, and the message
+/// authentication code over all values (`sops.mac`) is verified, so that files with values added,
+/// removed or reordered outside of SOPS fail to load.
+///
+/// # Note
+///
+/// SOPS includes the comments of YAML files in the message authentication code, but they are not
+/// read from YAML files here. YAML files with comments fail to load with
+/// [SourceError::SopsMacMismatch].
+#[derive(Debug)]
+pub struct SopsSource {
+    path: PathBuf,
+    key: AgeKey,
+    format: Option<SopsFormat>,
+}
+
+impl SopsSource {
+    pub fn new<Pa: AsRef<Path>>(path: Pa, key: AgeKey) -> Result<Self, SourceError> {
+        Ok(SopsSource {
+            path: path.as_ref().to_path_buf(),
+            key,
+            format: None,
+        })
+    }
+
+    /// Set the format of the file instead of detecting it from the file extension
+    pub fn format(mut self, format: SopsFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    fn parse(&self, buf: Vec<u8>) -> Result<ConfigObject, SourceError> {
+        let format = self
+            .format
+            .or_else(|| SopsFormat::from_path(&self.path))
+            .ok_or_else(|| {
+                let msg = format!("Unknown format of '{}'", self.path.display());
+                SourceError::SopsMetadata(msg)
+            })?;
+
+        let (mut document, spans, comments) = format.parse(buf)?;
+        let metadata = document
+            .remove(METADATA_KEY)
+            .ok_or_else(|| SourceError::SopsMetadata("Missing 'sops' metadata".to_string()))?;
+
+        let data_key = self.data_key(&metadata)?;
+        let cipher = SopsCipher::new_from_slice(&data_key)
+            .map_err(|_| SourceError::SopsMetadata("Invalid data key".to_string()))?;
+        let mut decryptor = Decryptor {
+            cipher,
+            mac: Sha512::new(),
+            mac_only_encrypted: std::matches!(
+                metadata.get("mac_only_encrypted"),
+                Some(Tree::Leaf(Value::Bool(true)))
+            ),
+        };
+        decryptor.tree(&mut document, &mut Vec::new())?;
+        decryptor.verify(&metadata)?;
+
+        let desc = ConfigSourceDescription::Path(self.path.clone());
+        Ok(ConfigObject::new(Box::new(Value::from(document)), desc)
+            .with_spans(spans)
+            .with_comments(comments))
+    }
+
+    /// Decrypt the data key with the first age recipient that matches one of our identities
+    fn data_key(&self, metadata: &Tree) -> Result<Vec<u8>, SourceError> {
+        let recipients = match metadata.get("age") {
+            Some(Tree::List(recipients)) => recipients,
+            _ => return Err(SourceError::SopsMetadata("No age recipients".to_string())),
+        };
+
+        let identities = self.key.identities()?;
+        recipients
+            .iter()
+            .filter_map(|recipient| recipient.get("enc")?.as_str())
+            .find_map(|enc| crate::source::age::decrypt(&identities, enc.as_bytes()).ok())
+            .ok_or(SourceError::SopsNoMatchingKey)
+    }
+}
+
+/// A parsed SOPS document
+///
+/// Unlike [Value], maps keep the order of the document, which the MAC is computed in.
+#[derive(Debug)]
+enum Tree {
+    Leaf(Value),
+    List(Vec<Tree>),
+    Map(Vec<(String, Tree)>),
+}
+
+impl Tree {
+    fn get(&self, key: &str) -> Option<&Tree> {
+        match self {
+            Tree::Map(map) => map.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Tree> {
+        match self {
+            Tree::Map(map) => {
+                let index = map.iter().position(|(k, _)| k == key)?;
+                Some(map.remove(index).1)
+            }
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Tree::Leaf(Value::Str(s)) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<Tree> for Value {
+    fn from(tree: Tree) -> Self {
+        match tree {
+            Tree::Leaf(value) => value,
+            Tree::List(list) => Value::List(list.into_iter().map(Value::from).collect()),
+            Tree::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Tree {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TreeVisitor)
+    }
+}
+
+struct TreeVisitor;
+
+impl<'de> serde::de::Visitor<'de> for TreeVisitor {
+    type Value = Tree;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a SOPS document")
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::Bool(b)))
+    }
+
+    fn visit_i64<E>(self, i: i64) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::from(i)))
+    }
+
+    fn visit_u64<E>(self, u: u64) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::from(u)))
+    }
+
+    fn visit_f64<E>(self, f: f64) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::from(f)))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::from(s)))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::Str(s)))
+    }
+
+    fn visit_unit<E>(self) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::Null))
+    }
+
+    fn visit_none<E>(self) -> Result<Tree, E> {
+        Ok(Tree::Leaf(Value::Null))
+    }
+
+    fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Tree, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Tree, A::Error> {
+        let mut list = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            list.push(value);
+        }
+        Ok(Tree::List(list))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Tree, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            #[cfg(feature = "toml")]
+            if key == TOML_DATETIME_FIELD {
+                let datetime = map.next_value::<String>()?;
+                return datetime
+                    .parse()
+                    .map(|datetime| Tree::Leaf(Value::Datetime(datetime)))
+                    .map_err(serde::de::Error::custom);
+            }
+            entries.push((key, map.next_value()?));
+        }
+        Ok(Tree::Map(entries))
+    }
+}
+
+/// Decrypts the values of a document and computes the MAC over them
+struct Decryptor {
+    cipher: SopsCipher,
+    mac: Sha512,
+    mac_only_encrypted: bool,
+}
+
+impl Decryptor {
+    /// Decrypt all encrypted values in `tree`, which is found at `path`
+    ///
+    /// Lists do not add to the path, all elements of a list are authenticated with the path of
+    /// the list.
+    fn tree(&mut self, tree: &mut Tree, path: &mut Vec<String>) -> Result<(), SourceError> {
+        match tree {
+            Tree::Map(map) => {
+                for (key, value) in map.iter_mut() {
+                    path.push(key.clone());
+                    self.tree(value, path)?;
+                    path.pop();
+                }
+            }
+            Tree::List(list) => {
+                for value in list.iter_mut() {
+                    self.tree(value, path)?;
+                }
+            }
+            Tree::Leaf(value) => {
+                let encrypted = match value {
+                    Value::Str(s) if s.starts_with(ENCRYPTED_PREFIX) => {
+                        let aad = format!("{}:", path.join(":"));
+                        let decrypted = decrypt_value(&self.cipher, s, &aad)
+                            .ok_or_else(|| SourceError::SopsDecrypt(path.join(".")))?;
+                        *value = decrypted;
+                        true
+                    }
+                    _ => false,
+                };
+                if encrypted || !self.mac_only_encrypted {
+                    self.mac.update(mac_bytes(value).as_bytes());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compare the MAC over the decrypted values with the MAC in the metadata
+    ///
+    /// The MAC is encrypted with the `lastmodified` timestamp of the metadata as additional data.
+    fn verify(self, metadata: &Tree) -> Result<(), SourceError> {
+        let lastmodified = match metadata.get("lastmodified") {
+            Some(Tree::Leaf(Value::Str(s))) => Cow::Borrowed(s.as_str()),
+            Some(Tree::Leaf(Value::Datetime(datetime))) => Cow::Owned(datetime.to_string()),
+            _ => {
+                return Err(SourceError::SopsMetadata(
+                    "Missing 'lastmodified'".to_string(),
+                ))
+            }
+        };
+        let mac = metadata
+            .get("mac")
+            .and_then(Tree::as_str)
+            .ok_or_else(|| SourceError::SopsMetadata("Missing 'mac'".to_string()))?;
+        let mac = decrypt_value(&self.cipher, mac, &lastmodified)
+            .ok_or_else(|| SourceError::SopsDecrypt("sops.mac".to_string()))?;
+
+        if mac != Value::Str(format!("{:X}", self.mac.finalize())) {
+            return Err(SourceError::SopsMacMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// The bytes of a decrypted value in the MAC, formatted like SOPS does
+fn mac_bytes(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::Str(s) => Cow::Borrowed(s),
+        Value::Bool(true) => Cow::Borrowed("True"),
+        Value::Bool(false) => Cow::Borrowed("False"),
+        Value::Number(Number::PosInt(u)) => Cow::Owned(u.to_string()),
+        Value::Number(Number::NegInt(i)) => Cow::Owned(i.to_string()),
+        Value::Number(Number::Float(f)) => Cow::Owned(f.to_string()),
+        Value::Datetime(datetime) => Cow::Owned(datetime.to_string()),
+        _ => Cow::Borrowed(""),
+    }
+}
+
+/// Decrypt a value of the form `ENC[AES256_GCM,data:...,iv:...,tag:...,type:...]`
+fn decrypt_value(cipher: &SopsCipher, encrypted: &str, aad: &str) -> Option<Value> {
+    let fields = encrypted
+        .strip_prefix(ENCRYPTED_PREFIX)?
+        .strip_suffix(']')?
+        .split(',')
+        .map(|field| field.split_once(':'))
+        .collect::<Option<Vec<_>>>()?;
+    let field = |name: &str| fields.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+    let decode = |name: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(field(name)?)
+            .ok()
+    };
+
+    let iv = decode("iv")?;
+    if iv.len() != 32 {
+        return None;
+    }
+    let mut msg = decode("data")?;
+    msg.extend(decode("tag")?);
+
+    let nonce = aes_gcm::Nonce::<U32>::from_slice(&iv);
+    let plaintext = cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: &msg,
+                aad: aad.as_bytes(),
+            },
+        )
+        .ok()?;
+    let plaintext = String::from_utf8(plaintext).ok()?;
+
+    match field("type")? {
+        "str" | "bytes" => Some(Value::Str(plaintext)),
+        "int" => plaintext.parse::<i64>().ok().map(Value::from),
+        "float" => plaintext.parse::<f64>().ok().map(Value::from),
+        "bool" => match plaintext.to_ascii_lowercase().as_str() {
+            "true" | "t" | "1" => Some(Value::Bool(true)),
+            "false" | "f" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl ConfigSource for SopsSource {
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        self.parse(std::fs::read(&self.path)?)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for SopsSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        self.parse(tokio::fs::read(&self.path).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes_gcm::aead::AeadCore;
+    use age::secrecy::ExposeSecret;
+
+    use super::*;
+
+    const LASTMODIFIED: &str = "2024-05-01T12:00:00Z";
+
+    /// Encrypt a value the way SOPS does
+    fn encrypt_value(cipher: &SopsCipher, plaintext: &str, ty: &str, aad: &str) -> String {
+        let iv = SopsCipher::generate_nonce(&mut aes_gcm::aead::OsRng);
+        let payload = Payload {
+            msg: plaintext.as_bytes(),
+            aad: aad.as_bytes(),
+        };
+        let out = cipher.encrypt(&iv, payload).unwrap();
+        let (data, tag) = out.split_at(out.len() - 16);
+        let b64 = base64::engine::general_purpose::STANDARD;
+        format!(
+            "ENC[AES256_GCM,data:{},iv:{},tag:{},type:{}]",
+            b64.encode(data),
+            b64.encode(iv),
+            b64.encode(tag),
+            ty
+        )
+    }
+
+    /// Compute and encrypt the MAC over the plaintext `values` the way SOPS does
+    fn encrypt_mac(cipher: &SopsCipher, values: &[&str]) -> String {
+        let mut mac = Sha512::new();
+        for value in values {
+            mac.update(value.as_bytes());
+        }
+        let mac = format!("{:X}", mac.finalize());
+        encrypt_value(cipher, &mac, "str", LASTMODIFIED)
+    }
+
+    /// A JSON string literal
+    fn quote(s: &str) -> String {
+        serde_json::Value::from(s).to_string()
+    }
+
+    fn get<'a>(object: &'a ConfigObject, key: &str) -> &'a dyn crate::ConfigElement {
+        let mut accessor = crate::ParsableAccessor::parse(&key).unwrap();
+        object.get(&mut accessor).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_sops_source() {
+        let identity = age::x25519::Identity::generate();
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), identity.to_string().expose_secret()).unwrap();
+
+        let data_key = SopsCipher::generate_key(&mut aes_gcm::aead::OsRng);
+        let cipher = SopsCipher::new(&data_key);
+        let enc = age::encrypt_and_armor(&identity.to_public(), &data_key).unwrap();
+
+        // Not sorted by key, the MAC is computed in document order
+        let password = encrypt_value(&cipher, "hunter2", "str", "database:password:");
+        let mac = encrypt_mac(
+            &cipher,
+            &["app", "hunter2", "5432", "a.example.com", "False"],
+        );
+        let document = |user: &str, extra: &str| {
+            format!(
+                r#"{{
+    "database": {{
+        "user": {},
+        "password": {},
+        "port": {}
+    }},
+    "hosts": [{}],{}
+    "debug": {},
+    "sops": {{
+        "age": [{{ "recipient": {}, "enc": {} }}],
+        "lastmodified": {},
+        "mac": {},
+        "version": "3.8.1"
+    }}
+}}"#,
+                user,
+                quote(&password),
+                quote(&encrypt_value(&cipher, "5432", "int", "database:port:")),
+                quote(&encrypt_value(&cipher, "a.example.com", "str", "hosts:")),
+                extra,
+                quote(&encrypt_value(&cipher, "False", "bool", "debug:")),
+                quote(&identity.to_public().to_string()),
+                quote(&enc),
+                quote(LASTMODIFIED),
+                quote(&mac),
+            )
+        };
+        let file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        let valid = document(r#""app""#, "");
+        std::fs::write(file.path(), &valid).unwrap();
+
+        let source = SopsSource::new(file.path(), AgeKey::from_file(key_file.path())).unwrap();
+        let object = source.load().unwrap();
+        assert_eq!(get(&object, "database.user").as_str(), Some("app"));
+        let mut accessor = crate::ParsableAccessor::parse(&"database.user").unwrap();
+        let view = object.get_with_description(&mut accessor).unwrap().unwrap();
+        let span = view.span().unwrap();
+        assert_eq!(&valid[span.range()], r#""app""#);
+        assert_eq!(get(&object, "database.password").as_str(), Some("hunter2"));
+        assert_eq!(get(&object, "database.port").as_u16(), Some(5432));
+        assert_eq!(get(&object, "hosts.0").as_str(), Some("a.example.com"));
+        assert_eq!(get(&object, "debug").as_bool(), Some(false));
+
+        // A value moved to another key fails to authenticate
+        std::fs::write(file.path(), document(&quote(&password), "")).unwrap();
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::SopsDecrypt(path)) if path == "database.user"
+        ));
+
+        // Values added or changed outside of SOPS do not match the MAC
+        std::fs::write(file.path(), document(r#""app""#, r#""admin": true,"#)).unwrap();
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::SopsMacMismatch)
+        ));
+        std::fs::write(file.path(), document(r#""root""#, "")).unwrap();
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::SopsMacMismatch)
+        ));
+
+        let other = age::x25519::Identity::generate();
+        std::fs::write(key_file.path(), other.to_string().expose_secret()).unwrap();
+        std::fs::write(file.path(), &valid).unwrap();
+        assert!(std::matches!(
+            source.load(),
+            Err(SourceError::SopsNoMatchingKey)
+        ));
+    }
+
+    #[test]
+    fn test_sops_source_yaml() {
+        let identity = age::x25519::Identity::generate();
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), identity.to_string().expose_secret()).unwrap();
+
+        let data_key = SopsCipher::generate_key(&mut aes_gcm::aead::OsRng);
+        let cipher = SopsCipher::new(&data_key);
+        let enc = age::encrypt_and_armor(&identity.to_public(), &data_key).unwrap();
+        let yaml = format!(
+            "api:\n    token: {}\nsops:\n    lastmodified: \"{}\"\n    mac: {}\n    age:\n        - recipient: {}\n          enc: |\n{}",
+            encrypt_value(&cipher, "s3cr3t", "str", "api:token:"),
+            LASTMODIFIED,
+            encrypt_mac(&cipher, &["s3cr3t"]),
+            identity.to_public(),
+            enc.lines()
+                .map(|line| format!("            {}\n", line))
+                .collect::<String>(),
+        );
+        let file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        std::fs::write(file.path(), yaml).unwrap();
+
+        let source = SopsSource::new(file.path(), AgeKey::from_file(key_file.path())).unwrap();
+        let object = source.load().unwrap();
+        assert_eq!(get(&object, "api.token").as_str(), Some("s3cr3t"));
+
+        let mut accessor = crate::ParsableAccessor::parse(&"sops").unwrap();
+        assert!(object.get(&mut accessor).unwrap().is_none());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_sops_source_toml() {
+        let identity = age::x25519::Identity::generate();
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), identity.to_string().expose_secret()).unwrap();
+
+        let data_key = SopsCipher::generate_key(&mut aes_gcm::aead::OsRng);
+        let cipher = SopsCipher::new(&data_key);
+        let enc = age::encrypt_and_armor(&identity.to_public(), &data_key).unwrap();
+        let toml = format!(
+            "started = 1979-05-27T07:32:00Z\ntoken = {}\n\n[[sops.age]]\nrecipient = {}\nenc = {}\n\n[sops]\nlastmodified = {}\nmac = {}\n",
+            quote(&encrypt_value(&cipher, "s3cr3t", "str", "token:")),
+            quote(&identity.to_public().to_string()),
+            quote(&enc),
+            quote(LASTMODIFIED),
+            quote(&encrypt_mac(&cipher, &["1979-05-27T07:32:00Z", "s3cr3t"])),
+        );
+        let file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(file.path(), toml).unwrap();
+
+        let source = SopsSource::new(file.path(), AgeKey::from_file(key_file.path())).unwrap();
+        let object = source.load().unwrap();
+        assert_eq!(get(&object, "token").as_str(), Some("s3cr3t"));
+        assert_eq!(
+            get(&object, "started").as_datetime().unwrap().to_string(),
+            "1979-05-27T07:32:00Z"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! SQLite configuration source
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use rusqlite::types::ValueRef;
+use rusqlite::OpenFlags;
+
+use crate::description::ConfigSourceDescription;
+use crate::element::Number;
+use crate::object::ConfigObject;
+use crate::source::kv::KeyValueTree;
+use crate::source::kv::Scalar;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+const DEFAULT_TABLE: &str = "settings";
+
+/// A source that reads settings from a table of an SQLite database
+///
+/// By default, the source reads the table `settings`, which is expected to have the columns
+/// `key`, `value` and `type`:
+///
+/// ```sql
+/// CREATE TABLE settings (key TEXT PRIMARY KEY, value, type TEXT);
+/// ```
+///
+/// Keys are split at `.`, and each part becomes one level of nested maps. The key
+/// `database.port` becomes accessible as `database.port`.
+///
+/// The `type` column decides how a value is interpreted. It may be one of `bool`, `int`,
+/// `float`, `string` or `blob` (case-insensitive). If it is `NULL`, the storage class of the value
+/// decides: `INTEGER` values become integers, `REAL` values become floats, `TEXT` values become
+/// strings, `BLOB` values become bytes and `NULL` values become null.
+///
+/// Use [SqliteSource::table] to read another table, or [SqliteSource::query] to provide a
+/// custom query. A custom query has to return the key and the value as the first two columns, and
+/// may return the type as a third column.
+///
+/// The [ConfigSourceDescription] of the source is [ConfigSourceDescription::Table], naming the
+/// database file and the table (or the custom query).
+///
+/// # Note
+///
+/// The database is opened read-only and closed again after loading. Loading is synchronous, also
+/// when the source is loaded as [AsyncConfigSource](crate::AsyncConfigSource).
+#[derive(Debug, Clone)]
+pub struct SqliteSource {
+    path: PathBuf,
+    table: String,
+    query: Option<String>,
+}
+
+impl SqliteSource {
+    /// Create a source that reads the table `settings` from the database file at `path`
+    pub fn new<Pa: AsRef<Path>>(path: Pa) -> Result<Self, SourceError> {
+        Ok(SqliteSource {
+            path: path.as_ref().to_path_buf(),
+            table: DEFAULT_TABLE.to_string(),
+            query: None,
+        })
+    }
+
+    /// Read the table `table` instead of `settings`
+    pub fn table<T: Into<String>>(mut self, table: T) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Read the settings with a custom query instead of from a table
+    pub fn query<Q: Into<String>>(mut self, query: Q) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    fn sql(&self) -> String {
+        match self.query.as_ref() {
+            Some(query) => query.clone(),
+            None => format!(
+                "SELECT key, value, type FROM \"{}\"",
+                self.table.replace('"', "\"\"")
+            ),
+        }
+    }
+
+    fn description(&self) -> ConfigSourceDescription {
+        ConfigSourceDescription::Table {
+            path: self.path.clone(),
+            table: self.query.as_ref().unwrap_or(&self.table).clone(),
+        }
+    }
+}
+
+/// Convert one value of the settings table, with an optional type name, into a scalar
+fn to_scalar(key: &str, value: ValueRef<'_>, ty: Option<&str>) -> Result<Scalar, SourceError> {
+    let invalid = |reason: String| SourceError::InvalidValue {
+        key: key.to_string(),
+        reason,
+    };
+    let text = |bytes: &[u8]| {
+        std::str::from_utf8(bytes)
+            .map(str::to_string)
+            .map_err(|e| invalid(e.to_string()))
+    };
+
+    let ty = ty.map(str::to_ascii_lowercase);
+    let scalar = match (ty.as_deref(), value) {
+        (_, ValueRef::Null) => Scalar::Null,
+
+        (None, ValueRef::Integer(i)) => Scalar::Number(i.into(), None),
+        (None, ValueRef::Real(f)) => Scalar::Number(f.into(), None),
+        (None, ValueRef::Text(t)) => Scalar::Str(text(t)?),
+
+        (Some("bool" | "boolean"), ValueRef::Integer(i)) => Scalar::Bool(i != 0, None),
+        (Some("bool" | "boolean"), ValueRef::Text(t)) => match text(t)?.as_str() {
+            "true" | "1" => Scalar::Bool(true, None),
+            "false" | "0" => Scalar::Bool(false, None),
+            other => return Err(invalid(format!("'{}' is not a boolean", other))),
+        },
+
+        (Some("int" | "integer"), ValueRef::Integer(i)) => Scalar::Number(i.into(), None),
+        (Some("int" | "integer"), ValueRef::Text(t)) => {
+            let t = text(t)?;
+            let i = t
+                .trim()
+                .parse::<i128>()
+                .map(Number::from)
+                .or_else(|_| t.trim().parse::<u128>().map(Number::from))
+                .map_err(|_| invalid(format!("'{}' is not an integer", t)))?;
+            Scalar::Number(i, None)
+        }
+
+        (Some("float" | "real"), ValueRef::Integer(i)) => Scalar::Number((i as f64).into(), None),
+        (Some("float" | "real"), ValueRef::Real(f)) => Scalar::Number(f.into(), None),
+        (Some("float" | "real"), ValueRef::Text(t)) => {
+            let t = text(t)?;
+            let f = t
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("'{}' is not a float", t)))?;
+            Scalar::Number(f.into(), None)
+        }
+
+        (Some("string" | "text"), ValueRef::Integer(i)) => Scalar::Str(i.to_string()),
+        (Some("string" | "text"), ValueRef::Real(f)) => Scalar::Str(f.to_string()),
+        (Some("string" | "text"), ValueRef::Text(t)) => Scalar::Str(text(t)?),
+
+        (None | Some("blob" | "bytes"), ValueRef::Blob(b)) => Scalar::Bytes(b.to_vec()),
+        (Some("blob" | "bytes"), ValueRef::Text(t)) => Scalar::Bytes(t.to_vec()),
+        (Some(ty), ValueRef::Blob(_)) => {
+            return Err(invalid(format!("BLOB is not of type '{}'", ty)))
+        }
+        (Some(ty @ ("bool" | "boolean" | "int" | "integer")), ValueRef::Real(f)) => {
+            return Err(invalid(format!("{} is not of type '{}'", f, ty)))
+        }
+        (Some(ty), _) => return Err(invalid(format!("unknown type '{}'", ty))),
+    };
+
+    Ok(scalar)
+}
+
+impl ConfigSource for SqliteSource {
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        let connection = rusqlite::Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let mut statement = connection.prepare(&self.sql())?;
+        let has_type = statement.column_count() > 2;
+
+        let mut tree = KeyValueTree::new();
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let key = row.get::<_, String>(0)?;
+            let ty = if has_type {
+                row.get::<_, Option<String>>(2)?
+            } else {
+                None
+            };
+
+            let value = to_scalar(&key, row.get_ref(1)?, ty.as_deref())?;
+            tree.insert(key.split('.'), Box::new(value))?;
+        }
+
+        Ok(ConfigObject::new(Box::new(tree), self.description()))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for SqliteSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        self.load()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database(sql: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let connection = rusqlite::Connection::open(file.path()).unwrap();
+        connection.execute_batch(sql).unwrap();
+        file
+    }
+
+    fn get<'a>(object: &'a ConfigObject, key: &str) -> &'a dyn crate::ConfigElement {
+        let mut accessor = crate::ParsableAccessor::parse(&key).unwrap();
+        object.get(&mut accessor).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_sqlite_source_settings_table() {
+        let db = database(
+            r#"
+            CREATE TABLE settings (key TEXT PRIMARY KEY, value, type TEXT);
+            CREATE TABLE documents (id INTEGER PRIMARY KEY, body TEXT);
+            INSERT INTO settings VALUES
+                ('ui.dark_mode', 1, 'bool'),
+                ('ui.font.size', 12, NULL),
+                ('ui.font.scale', '1.5', 'float'),
+                ('ui.font.family', 'Fira Sans', NULL),
+                ('window.title', 42, 'string'),
+                ('window.icon', NULL, NULL),
+                ('window.logo', X'89504E47', NULL);
+            "#,
+        );
+
+        let object = SqliteSource::new(db.path()).unwrap().load().unwrap();
+        assert_eq!(get(&object, "ui.dark_mode").as_bool(), Some(true));
+        assert_eq!(get(&object, "ui.font.size").as_u8(), Some(12));
+        assert_eq!(get(&object, "ui.font.scale").as_f64(), Some(1.5));
+        assert_eq!(get(&object, "ui.font.family").as_str(), Some("Fira Sans"));
+        assert_eq!(get(&object, "window.title").as_str(), Some("42"));
+        assert!(get(&object, "window.icon").is_null());
+        assert_eq!(
+            get(&object, "window.logo").as_bytes(),
+            Some(&b"\x89PNG"[..])
+        );
+
+        let mut accessor = crate::ParsableAccessor::parse(&"ui.dark_mode").unwrap();
+        let view = object.get_with_description(&mut accessor).unwrap().unwrap();
+        match view.description() {
+            ConfigSourceDescription::Table { path, table } => {
+                assert_eq!(path, db.path());
+                assert_eq!(table, "settings");
+            }
+            other => panic!("Unexpected description: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sqlite_source_query() {
+        let db = database(
+            r#"
+            CREATE TABLE prefs (name TEXT, val, profile TEXT);
+            INSERT INTO prefs VALUES ('editor.tab_width', 4, 'default'), ('editor.tab_width', 8, 'other');
+            "#,
+        );
+
+        let object = SqliteSource::new(db.path())
+            .unwrap()
+            .query("SELECT name, val FROM prefs WHERE profile = 'default'")
+            .load()
+            .unwrap();
+        assert_eq!(get(&object, "editor.tab_width").as_u8(), Some(4));
+    }
+
+    #[test]
+    fn test_sqlite_source_invalid_value() {
+        let db = database(
+            r#"
+            CREATE TABLE settings (key TEXT PRIMARY KEY, value, type TEXT);
+            INSERT INTO settings VALUES ('retries', 'many', 'int');
+            "#,
+        );
+
+        let err = SqliteSource::new(db.path()).unwrap().load().unwrap_err();
+        assert!(std::matches!(err, SourceError::InvalidValue { key, .. } if key == "retries"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt::Debug;
+use std::io::Read;
+use std::sync::Mutex;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::format::FormatParser;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+/// A source that parses what is piped into the standard input of the process
+///
+/// Standard input can only be read once. Thus, the input is read when the source is loaded for the
+/// first time and cached afterwards.
+///
+/// # Reloading
+///
+/// Reloading a configuration that contains this source does _not_ read standard input again, but
+/// parses the cached content. The layer created by this source therefore never changes on reload.
+#[derive(Debug)]
+pub struct StdinSource<P>
+where
+    P: FormatParser + std::fmt::Debug,
+{
+    cache: Mutex<Option<Vec<u8>>>,
+    _pd: std::marker::PhantomData<P>,
+}
+
+impl<P: FormatParser> StdinSource<P> {
+    pub fn new() -> Result<Self, SourceError> {
+        Ok(StdinSource {
+            cache: Mutex::new(None),
+            _pd: std::marker::PhantomData,
+        })
+    }
+
+    /// Create the source from an already opened reader instead of standard input
+    ///
+    /// The reader is read to the end immediately and its content is cached just like the content
+    /// of standard input would be.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, SourceError> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        Ok(StdinSource {
+            cache: Mutex::new(Some(buf)),
+            _pd: std::marker::PhantomData,
+        })
+    }
+
+    fn cached(&self) -> Option<Vec<u8>> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn store(&self, buf: Vec<u8>) -> Vec<u8> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get_or_insert(buf)
+            .clone()
+    }
+}
+
+impl<P> ConfigSource for StdinSource<P>
+where
+    P: FormatParser + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        let buf = match self.cached() {
+            Some(buf) => buf,
+            None => {
+                let mut buf = Vec::new();
+                std::io::stdin().lock().read_to_end(&mut buf)?;
+                self.store(buf)
+            }
+        };
+
+        let (element, spans, comments) = P::parse_with_comments(buf)?;
+        Ok(
+            ConfigObject::new(Box::new(element), ConfigSourceDescription::Stdin)
+                .with_spans(spans)
+                .with_comments(comments),
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<P> crate::source::AsyncConfigSource for StdinSource<P>
+where
+    P: FormatParser + Send + Sync + Debug,
+    <P as FormatParser>::Output: 'static,
+{
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        use tokio::io::AsyncReadExt;
+
+        let buf = match self.cached() {
+            Some(buf) => buf,
+            None => {
+                let mut buf = Vec::new();
+                tokio::io::stdin().read_to_end(&mut buf).await?;
+                self.store(buf)
+            }
+        };
+
+        let (element, spans, comments) = P::parse_with_comments(buf)?;
+        Ok(
+            ConfigObject::new(Box::new(element), ConfigSourceDescription::Stdin)
+                .with_spans(spans)
+                .with_comments(comments),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::source::JsonFormatParser;
+
+    #[test]
+    fn test_stdin_source_reload_uses_cache() {
+        let source =
+            StdinSource::<JsonFormatParser>::from_reader(r#"{"key": "value"}"#.as_bytes()).unwrap();
+
+        for _ in 0..2 {
+            let object = source.load().unwrap();
+            let mut accessor = crate::ParsableAccessor::parse(&"key").unwrap();
+            let value = object.get(&mut accessor).unwrap().unwrap();
+            assert_eq!(value.as_str(), Some("value"));
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use crate::config::ConfigConstructor;
+use crate::config::Layers;
 use crate::description::ConfigSourceDescription;
 use crate::object::ConfigObject;
+use crate::source::compression::Compression;
+use crate::source::compression::DEFAULT_MAX_DECOMPRESSED_SIZE;
 use crate::source::format::FormatParser;
 use crate::ConfigSource;
 
@@ -7,17 +11,93 @@ use super::SourceError;
 
 #[derive(Debug)]
 pub struct StringSource<P: FormatParser + std::fmt::Debug> {
-    source: String,
+    source: Vec<u8>,
+    compression: Compression,
+    max_decompressed_size: u64,
+    desc: ConfigSourceDescription,
+    validate: Option<fn(ConfigObject) -> Result<ConfigObject, SourceError>>,
     _pd: std::marker::PhantomData<P>,
 }
 
 impl<P: FormatParser> StringSource<P> {
     pub fn new(source: String) -> Result<Self, SourceError> {
+        Self::from_bytes(source)
+    }
+
+    /// Create the source from raw bytes, for example compressed content
+    pub fn from_bytes<B: Into<Vec<u8>>>(source: B) -> Result<Self, SourceError> {
         Ok(StringSource {
-            source,
+            source: source.into(),
+            compression: Compression::Auto,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            desc: ConfigSourceDescription::Custom("String".to_string()),
+            validate: None,
             _pd: std::marker::PhantomData,
         })
     }
+
+    /// Create the source for a file embedded with [include_config!](crate::include_config)
+    #[doc(hidden)]
+    pub fn included(source: &'static str, path: &'static str) -> Self {
+        StringSource {
+            source: source.as_bytes().to_vec(),
+            compression: Compression::None,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            desc: ConfigSourceDescription::Path(path.into()),
+            validate: None,
+            _pd: std::marker::PhantomData,
+        }
+    }
+
+    /// Set the compression codec of the content
+    ///
+    /// By default, the codec is detected from the content.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Fail loading if the decompressed content is larger than `max_size` bytes
+    ///
+    /// Defaults to 64 MiB.
+    pub fn max_decompressed_size(mut self, max_size: u64) -> Self {
+        self.max_decompressed_size = max_size;
+        self
+    }
+
+    /// Fail loading if `T` cannot be constructed from the content of this source alone
+    ///
+    /// This is useful for defaults, which should contain all values of the configuration.
+    pub fn validate<T>(mut self) -> Self
+    where
+        T: ConfigConstructor,
+        T::Error: std::error::Error + 'static,
+    {
+        self.validate = Some(|object| {
+            Layers::check::<T>(object).map_err(|e| SourceError::Validation(Box::new(e)))
+        });
+        self
+    }
+
+    fn parse(&self) -> Result<ConfigObject, SourceError>
+    where
+        <P as FormatParser>::Output: 'static,
+    {
+        let (element, spans, comments) = P::parse_with_comments(self.decompressed()?)?;
+        let object = ConfigObject::new(Box::new(element), self.desc.clone())
+            .with_spans(spans)
+            .with_comments(comments);
+
+        match self.validate {
+            Some(validate) => validate(object),
+            None => Ok(object),
+        }
+    }
+
+    fn decompressed(&self) -> Result<Vec<u8>, SourceError> {
+        self.compression
+            .decompress(self.source.clone(), self.max_decompressed_size)
+    }
 }
 
 impl<P> ConfigSource for StringSource<P>
@@ -26,10 +106,7 @@ where
     <P as FormatParser>::Output: 'static,
 {
     fn load(&self) -> Result<ConfigObject, SourceError> {
-        let element = P::parse(self.source.as_bytes().to_vec())?;
-
-        let desc = ConfigSourceDescription::Custom("String".to_string());
-        Ok(ConfigObject::new(Box::new(element), desc))
+        self.parse()
     }
 }
 
@@ -41,10 +118,7 @@ where
     <P as FormatParser>::Output: 'static,
 {
     async fn load_async(&self) -> Result<ConfigObject, SourceError> {
-        let element = P::parse(self.source.as_bytes().to_vec())?;
-
-        let desc = ConfigSourceDescription::Custom("String".to_string());
-        Ok(ConfigObject::new(Box::new(element), desc))
+        self.parse()
     }
 }
 
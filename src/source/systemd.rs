@@ -0,0 +1,199 @@
+//! systemd credentials configuration source
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use crate::description::ConfigSourceDescription;
+use crate::element::Value;
+use crate::object::ConfigObject;
+use crate::source::format::parse_boxed;
+use crate::source::format::FormatParser;
+use crate::source::format::ParseFn;
+use crate::source::kv::KeyValueTree;
+use crate::ConfigSource;
+
+use super::SourceError;
+
+const CREDENTIALS_DIRECTORY: &str = "CREDENTIALS_DIRECTORY";
+
+/// A source that reads the credentials systemd passes to a service
+///
+/// systemd exposes the credentials configured with `LoadCredential=`, `SetCredential=` and
+/// friends as files in the directory named by the `$CREDENTIALS_DIRECTORY` environment variable.
+/// Each file becomes one value, with the name of the credential as its key. Names are split at
+/// `.`, so the credential `database.password` becomes accessible as `database.password`.
+///
+/// Credentials are used as strings, unchanged. Use [SystemdCredentialsSource::format] to parse a
+/// credential with a [FormatParser] instead.
+///
+/// If `$CREDENTIALS_DIRECTORY` is not set, the source is empty, unless it is marked as required
+/// with [SystemdCredentialsSource::required].
+///
+/// The [ConfigSourceDescription] of each value is the path of its credential file.
+#[derive(Debug)]
+pub struct SystemdCredentialsSource {
+    directory: Option<PathBuf>,
+    required: bool,
+    formats: HashMap<String, ParseFn>,
+}
+
+impl SystemdCredentialsSource {
+    pub fn new() -> Result<Self, SourceError> {
+        Ok(SystemdCredentialsSource {
+            directory: None,
+            required: false,
+            formats: HashMap::new(),
+        })
+    }
+
+    /// Fail loading if `$CREDENTIALS_DIRECTORY` is not set
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Read credentials from `directory` instead of `$CREDENTIALS_DIRECTORY`
+    pub fn directory<D: Into<PathBuf>>(mut self, directory: D) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Parse the credential `name` with `P` instead of using it as a string
+    pub fn format<P, N>(mut self, name: N) -> Self
+    where
+        P: FormatParser,
+        <P as FormatParser>::Output: 'static,
+        N: Into<String>,
+    {
+        self.formats.insert(name.into(), parse_boxed::<P>);
+        self
+    }
+
+    fn credentials_directory(&self) -> Result<Option<PathBuf>, SourceError> {
+        self.resolve_directory(std::env::var_os(CREDENTIALS_DIRECTORY))
+    }
+
+    /// The directory to read, given the value of `$CREDENTIALS_DIRECTORY`
+    fn resolve_directory(&self, env: Option<OsString>) -> Result<Option<PathBuf>, SourceError> {
+        if let Some(directory) = self.directory.as_ref() {
+            return Ok(Some(directory.clone()));
+        }
+
+        match env {
+            Some(directory) if !directory.is_empty() => Ok(Some(PathBuf::from(directory))),
+            _ if self.required => Err(SourceError::EnvVarNotSet(CREDENTIALS_DIRECTORY.to_string())),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl ConfigSource for SystemdCredentialsSource {
+    fn load(&self) -> Result<ConfigObject, SourceError> {
+        let directory = match self.credentials_directory()? {
+            Some(directory) => directory,
+            None => {
+                let desc = ConfigSourceDescription::Custom(CREDENTIALS_DIRECTORY.to_string());
+                return Ok(ConfigObject::new(Box::new(KeyValueTree::new()), desc));
+            }
+        };
+
+        let mut tree = KeyValueTree::new();
+        for entry in std::fs::read_dir(&directory)? {
+            let entry = entry?;
+            let name = match entry.file_name().into_string() {
+                Ok(name) if !name.starts_with('.') => name,
+                _ => continue,
+            };
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+            let buf = std::fs::read(&path)?;
+            let value = match self.formats.get(&name) {
+                Some(parse) => parse(buf)?,
+                None => {
+                    let s = String::from_utf8(buf).map_err(|e| SourceError::Custom(Box::new(e)))?;
+                    Box::new(Value::Str(s))
+                }
+            };
+
+            let desc = ConfigSourceDescription::Path(path);
+            tree.insert_with_description(name.split('.'), value, Some(desc))?;
+        }
+
+        Ok(ConfigObject::new(
+            Box::new(tree),
+            ConfigSourceDescription::Path(directory),
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for SystemdCredentialsSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        self.load()
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::source::JsonFormatParser;
+
+    fn get<'a>(object: &'a ConfigObject, key: &str) -> Option<&'a dyn crate::ConfigElement> {
+        let mut accessor = crate::ParsableAccessor::parse(&key).unwrap();
+        object.get(&mut accessor).unwrap()
+    }
+
+    #[test]
+    fn test_systemd_credentials_source() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join("database.password"), "1234").unwrap();
+        std::fs::write(directory.path().join("tls"), r#"{"port": 8443}"#).unwrap();
+
+        let source = SystemdCredentialsSource::new()
+            .unwrap()
+            .directory(directory.path())
+            .format::<JsonFormatParser, _>("tls");
+        let object = source.load().unwrap();
+
+        let password = get(&object, "database.password").unwrap();
+        assert_eq!(password.as_str(), Some("1234"));
+        assert_eq!(get(&object, "tls.port").unwrap().as_u16(), Some(8443));
+
+        let mut accessor = crate::ParsableAccessor::parse(&"database.password").unwrap();
+        let view = object.get_with_description(&mut accessor).unwrap().unwrap();
+        match view.description() {
+            ConfigSourceDescription::Path(path) => {
+                assert_eq!(path, &directory.path().join("database.password"))
+            }
+            other => panic!("Unexpected description: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_credentials_directory() {
+        let source = SystemdCredentialsSource::new().unwrap();
+        assert_eq!(source.resolve_directory(None).unwrap(), None);
+        assert_eq!(source.resolve_directory(Some("".into())).unwrap(), None);
+        assert_eq!(
+            source.resolve_directory(Some("/run/creds".into())).unwrap(),
+            Some(PathBuf::from("/run/creds"))
+        );
+
+        let source = source.required(true);
+        assert!(std::matches!(
+            source.resolve_directory(None),
+            Err(SourceError::EnvVarNotSet(var)) if var == CREDENTIALS_DIRECTORY
+        ));
+
+        let source = source.directory("/etc/creds");
+        assert_eq!(
+            source.resolve_directory(Some("/run/creds".into())).unwrap(),
+            Some(PathBuf::from("/etc/creds"))
+        );
+    }
+}
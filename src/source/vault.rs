@@ -0,0 +1,275 @@
+//! HashiCorp Vault KV v2 configuration source
+
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use reqwest::StatusCode;
+
+use crate::description::ConfigSourceDescription;
+use crate::object::ConfigObject;
+use crate::source::kv::KeyValueTree;
+
+use super::SourceError;
+
+const VAULT_TOKEN_HEADER: &str = "X-Vault-Token";
+
+/// How a [VaultSource] authenticates against Vault
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum VaultAuth {
+    /// Use a token directly
+    Token(String),
+
+    /// Log in with the AppRole auth method, mounted at `auth/approle`
+    AppRole { role_id: String, secret_id: String },
+}
+
+impl std::fmt::Debug for VaultAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultAuth::Token(_) => f.debug_tuple("Token").field(&"<redacted>").finish(),
+            VaultAuth::AppRole { role_id, .. } => f
+                .debug_struct("AppRole")
+                .field("role_id", role_id)
+                .field("secret_id", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+/// A lease or TTL that was reported by Vault while loading the source
+#[derive(Debug, Clone)]
+pub struct VaultLease {
+    /// The Vault path the lease belongs to
+    pub path: String,
+
+    /// The duration of the lease, as reported by Vault
+    pub duration: Duration,
+
+    /// Whether the lease can be renewed
+    pub renewable: bool,
+
+    /// The point in time at which the lease expires
+    pub expires_at: Instant,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LoginResponse {
+    auth: LoginAuth,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LoginAuth {
+    client_token: String,
+    lease_duration: u64,
+    renewable: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SecretResponse {
+    #[serde(default)]
+    lease_duration: u64,
+    #[serde(default)]
+    renewable: bool,
+    data: SecretData,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SecretData {
+    data: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default)]
+struct VaultState {
+    token: Option<(String, Option<VaultLease>)>,
+    leases: Vec<VaultLease>,
+}
+
+/// A source that reads secrets from a Vault KV version 2 secrets engine
+///
+/// Each secret is mounted at a key in the configuration: with
+/// `.secret("database", "myapp/db")`, the field `password` of the secret at `myapp/db` becomes
+/// accessible as `database.password`.
+///
+/// The [ConfigSourceDescription] of each value is the URI of its secret, it never contains the
+/// secret values themselves.
+///
+/// # Reloading
+///
+/// Vault reports leases for the login token and (for some secrets engines) for secrets.
+/// [VaultSource::leases] and [VaultSource::reload_due] can be used to find out when the
+/// configuration should be reloaded.
+pub struct VaultSource {
+    client: reqwest::Client,
+    address: url::Url,
+    mount: String,
+    auth: VaultAuth,
+    secrets: Vec<(String, String)>,
+    state: Mutex<VaultState>,
+}
+
+impl std::fmt::Debug for VaultSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultSource")
+            .field("address", &self.address)
+            .field("mount", &self.mount)
+            .field("auth", &self.auth)
+            .field("secrets", &self.secrets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VaultSource {
+    /// Create a source for the Vault server at `address`, for example `http://127.0.0.1:8200`
+    ///
+    /// The KV secrets engine is expected to be mounted at `secret`, use [VaultSource::mount] to
+    /// change that.
+    pub fn new(address: url::Url, auth: VaultAuth) -> Result<Self, SourceError> {
+        Ok(VaultSource {
+            client: reqwest::Client::new(),
+            address,
+            mount: "secret".to_string(),
+            auth,
+            secrets: Vec::new(),
+            state: Mutex::new(VaultState::default()),
+        })
+    }
+
+    /// Set the path the KV secrets engine is mounted at
+    pub fn mount<M: Into<String>>(mut self, mount: M) -> Self {
+        self.mount = mount.into();
+        self
+    }
+
+    /// Read the secret at `path` and make its fields accessible below `key`
+    ///
+    /// `key` may contain dots to mount the secret deeper in the configuration tree.
+    pub fn secret<K: Into<String>, P: Into<String>>(mut self, key: K, path: P) -> Self {
+        self.secrets.push((key.into(), path.into()));
+        self
+    }
+
+    /// Use a custom client, for example to configure timeouts or certificates
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// The leases reported by Vault when the source was loaded last
+    pub fn leases(&self) -> Vec<VaultLease> {
+        let state = self.lock_state();
+        state
+            .token
+            .iter()
+            .filter_map(|(_, lease)| lease.clone())
+            .chain(state.leases.iter().cloned())
+            .collect()
+    }
+
+    /// The point in time at which the first lease expires, if any
+    pub fn reload_due(&self) -> Option<Instant> {
+        self.leases().into_iter().map(|l| l.expires_at).min()
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, VaultState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn url(&self, path: &str) -> Result<url::Url, SourceError> {
+        self.address
+            .join(&format!("v1/{}", path))
+            .map_err(|e| SourceError::Custom(Box::new(e)))
+    }
+
+    fn check_status(&self, path: &str, status: StatusCode) -> Result<(), SourceError> {
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SourceError::HttpStatus {
+                url: self.url(path)?,
+                status: status.as_u16(),
+            })
+        }
+    }
+
+    /// Get a token, logging in if required
+    async fn token(&self) -> Result<String, SourceError> {
+        let (role_id, secret_id) = match &self.auth {
+            VaultAuth::Token(token) => return Ok(token.clone()),
+            VaultAuth::AppRole { role_id, secret_id } => (role_id, secret_id),
+        };
+
+        if let Some((token, lease)) = self.lock_state().token.as_ref() {
+            match lease {
+                Some(lease) if lease.expires_at <= Instant::now() => {}
+                _ => return Ok(token.clone()),
+            }
+        }
+
+        let path = "auth/approle/login";
+        let url = self.url(path)?;
+        let body = serde_json::json!({ "role_id": role_id, "secret_id": secret_id });
+        let response = self.client.post(url).json(&body).send().await?;
+        self.check_status(path, response.status())?;
+
+        let login = serde_json::from_slice::<LoginResponse>(&response.bytes().await?)?.auth;
+        let lease = (login.lease_duration > 0).then(|| {
+            let duration = Duration::from_secs(login.lease_duration);
+            VaultLease {
+                path: path.to_string(),
+                duration,
+                renewable: login.renewable,
+                expires_at: Instant::now() + duration,
+            }
+        });
+
+        self.lock_state().token = Some((login.client_token.clone(), lease));
+        Ok(login.client_token)
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::source::AsyncConfigSource for VaultSource {
+    async fn load_async(&self) -> Result<ConfigObject, SourceError> {
+        let token = self.token().await?;
+
+        let mut tree = KeyValueTree::new();
+        let mut leases = Vec::new();
+        for (key, path) in self.secrets.iter() {
+            let path = format!("{}/data/{}", self.mount, path);
+            let url = self.url(&path)?;
+            let response = self
+                .client
+                .get(url.clone())
+                .header(VAULT_TOKEN_HEADER, token.as_str())
+                .send()
+                .await?;
+            self.check_status(&path, response.status())?;
+
+            let secret = serde_json::from_slice::<SecretResponse>(&response.bytes().await?)?;
+            if secret.lease_duration > 0 {
+                let duration = Duration::from_secs(secret.lease_duration);
+                leases.push(VaultLease {
+                    path: path.clone(),
+                    duration,
+                    renewable: secret.renewable,
+                    expires_at: Instant::now() + duration,
+                });
+            }
+
+            for (field, value) in secret.data.data {
+                let desc = ConfigSourceDescription::Uri(url.clone());
+                let path = key.split('.').chain(std::iter::once(field.as_str()));
+                tree.insert_with_description(path, Box::new(value), Some(desc))?;
+            }
+        }
+
+        self.lock_state().leases = leases;
+
+        let desc = ConfigSourceDescription::Uri(self.address.clone());
+        Ok(ConfigObject::new(Box::new(tree), desc))
+    }
+}
@@ -0,0 +1,119 @@
+use std::ops::Range;
+
+use crate::path_map::PathMap;
+
+/// The position of a value in the document it was parsed from
+///
+/// Lines and columns start at 1. Columns count characters, not bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Span {
+    /// The byte offset of the start of the value
+    pub start: usize,
+
+    /// The byte offset after the end of the value
+    pub end: usize,
+
+    /// The line of the start of the value
+    pub line: usize,
+
+    /// The column of the start of the value
+    pub column: usize,
+}
+
+impl Span {
+    /// Create the span of the bytes `range` of `document`
+    ///
+    /// This scans the document up to the start of the span.
+    pub fn new(document: &str, range: Range<usize>) -> Self {
+        Lines::new(&document[..floor_char_boundary(document, range.start)]).span(range)
+    }
+
+    /// The bytes of the document covered by this span
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl std::fmt::Display for Span {
+    /// Formats the span as `line:column`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The start offsets of the lines of a document
+///
+/// Computed once per document, so that the line and column of each span are found by a binary
+/// search instead of scanning the document from its start.
+pub(crate) struct Lines<'a> {
+    document: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(document: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(document.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Lines { document, starts }
+    }
+
+    /// Create the span of the bytes `range` of the document
+    pub(crate) fn span(&self, range: Range<usize>) -> Span {
+        let start = floor_char_boundary(self.document, range.start);
+        let line = self
+            .starts
+            .partition_point(|&line_start| line_start <= start);
+        let line_start = self.starts[line - 1];
+
+        Span {
+            start: range.start,
+            end: range.end,
+            line,
+            column: self.document[line_start..start].chars().count() + 1,
+        }
+    }
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// The spans of the values of a parsed document, by their position in the document
+///
+/// A [FormatParser](crate::FormatParser) records the spans of the values it parses in
+/// [FormatParser::parse_with_spans](crate::FormatParser::parse_with_spans). They are returned
+/// from [ConfigView::span](crate::ConfigView::span).
+pub type Spans = PathMap<Span>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_position() {
+        let document = "a = 1\nkey = \"wört\"\nb = 2\n";
+        let start = document.find("\"w").unwrap();
+        let span = Span::new(document, start..start + 7);
+        assert_eq!((span.line, span.column), (2, 7));
+
+        let start = document.find("b =").unwrap();
+        let span = Span::new(document, start..start + 1);
+        assert_eq!(span.to_string(), "3:1");
+
+        let lines = Lines::new(document);
+        assert_eq!(lines.span(0..1).to_string(), "1:1");
+        assert_eq!(lines.span(start..start + 1), span);
+        assert_eq!(
+            lines.span(document.len()..document.len()).to_string(),
+            "4:1"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(all(feature = "json", feature = "base64", feature = "bytes"))]
+
+use config_rs_ng::Base64;
+use config_rs_ng::Config;
+use config_rs_ng::ConfigConstructor;
+use config_rs_ng::FromConfigElementError;
+use config_rs_ng::Hex;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::StringSource;
+
+#[derive(Debug, config_rs_ng::ConfigConstructor, config_rs_ng::FromConfigElement)]
+struct Tls {
+    fingerprint: Hex,
+    certificate: Base64<bytes::Bytes>,
+    key: Vec<u8>,
+}
+
+fn load(json: &str) -> Config {
+    Config::builder()
+        .load(Box::new(
+            StringSource::<JsonFormatParser>::new(json.to_string()).unwrap(),
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_encoded_bytes() {
+    let config = load(
+        r#"{
+            "fingerprint": "DEADbeef",
+            "certificate": "LS0tLS1CRUdJTg==",
+            "key": [1, 2, 255]
+        }"#,
+    );
+
+    let tls = Tls::construct_from(config.layers()).unwrap();
+    assert_eq!(tls.fingerprint.0, vec![0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(tls.certificate.0, bytes::Bytes::from_static(b"-----BEGIN"));
+    assert_eq!(tls.key, vec![1, 2, 255]);
+}
+
+#[test]
+fn test_encoded_bytes_errors() {
+    let config = load(r#"{ "fingerprint": "xyz", "certificate": 12, "key": "text" }"#);
+
+    assert!(std::matches!(
+        Tls::construct_from(config.layers()),
+        Err(FromConfigElementError::DecodeError {
+            encoding: "hex",
+            ..
+        })
+    ));
+
+    let config = load(r#"{ "fingerprint": "", "certificate": 12, "key": [] }"#);
+    assert!(std::matches!(
+        Tls::construct_from(config.layers()),
+        Err(FromConfigElementError::TypeError {
+            expected: "base64",
+            found: "i8",
+        })
+    ));
+}
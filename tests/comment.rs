@@ -0,0 +1,48 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use config_rs_ng::Config;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::StringSource;
+use config_rs_ng::TomlFormatParser;
+
+const DEFAULTS: &str = r#"
+[server]
+# The port to listen on
+port = 8080
+host = "localhost" # or an IP address
+"#;
+
+#[test]
+fn test_comments_of_layers() {
+    let config = Config::builder()
+        .load(Box::new(
+            StringSource::<TomlFormatParser>::new(DEFAULTS.to_string()).unwrap(),
+        ))
+        .load(Box::new(
+            StringSource::<JsonFormatParser>::new(r#"{ "server": { "port": 9090 } }"#.to_string())
+                .unwrap(),
+        ))
+        .build()
+        .unwrap();
+
+    // The value comes from the JSON layer, which has no comments
+    let view = config.layers().get_view("server.port").unwrap().unwrap();
+    assert_eq!(view.value().as_u16(), Some(9090));
+    assert!(view.comment().is_none());
+
+    let view = config.layers().get_view("server.host").unwrap().unwrap();
+    let comment = view.comment().unwrap();
+    assert_eq!(comment.leading, None);
+    assert_eq!(comment.trailing.as_deref(), Some("or an IP address"));
+
+    let config = Config::builder()
+        .load(Box::new(
+            StringSource::<TomlFormatParser>::new(DEFAULTS.to_string()).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    let view = config.layers().get_view("server.port").unwrap().unwrap();
+    let comment = view.comment().unwrap();
+    assert_eq!(comment.leading.as_deref(), Some("The port to listen on"));
+    assert_eq!(comment.trailing, None);
+}
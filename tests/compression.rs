@@ -0,0 +1,54 @@
+#![cfg(all(feature = "async", feature = "json", feature = "gzip"))]
+
+use std::io::Write;
+
+use config_rs_ng::AsyncConfig;
+use config_rs_ng::Compression;
+use config_rs_ng::FileSource;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::StringSource;
+
+fn gzip(content: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(content).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn test_compressed_file_source() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(&gzip(br#"{"routes": {"default": "10.0.0.1"}}"#))
+        .unwrap();
+
+    let config = AsyncConfig::builder()
+        .load(Box::new(
+            FileSource::<JsonFormatParser>::new(file.path()).unwrap(),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let route = config.layers().get("routes.default").unwrap().unwrap();
+    assert_eq!(route.as_str(), Some("10.0.0.1"));
+}
+
+#[tokio::test]
+async fn test_compressed_string_source_size_limit() {
+    let content = format!(r#"{{"allow": "{}"}}"#, "x".repeat(4096));
+    let source = StringSource::<JsonFormatParser>::from_bytes(gzip(content.as_bytes()))
+        .unwrap()
+        .compression(Compression::Gzip)
+        .max_decompressed_size(1024);
+
+    let err = AsyncConfig::builder()
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap_err();
+    assert!(std::matches!(
+        err,
+        config_rs_ng::ConfigError::SourceError(config_rs_ng::SourceError::DecompressedSizeLimit(
+            1024
+        ))
+    ));
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "consul")]
+
+mod common;
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::Response;
+use config_rs_ng::AsyncConfig;
+use config_rs_ng::AsyncConfigSource;
+use config_rs_ng::ConfigSourceDescription;
+use config_rs_ng::ConsulSource;
+
+/// `port` is the base64 encoded value of `myapp/database/port`
+fn kv_response(index: u64, port: &str) -> Response {
+    let body = serde_json::json!([
+        { "Key": "myapp/", "Value": null, "ModifyIndex": 1 },
+        { "Key": "myapp/database/host", "Value": "bG9jYWxob3N0", "ModifyIndex": 2 },
+        { "Key": "myapp/database/port", "Value": port, "ModifyIndex": index },
+    ]);
+
+    Response::new(200, body.to_string())
+        .header("Content-Type", "application/json")
+        .header("X-Consul-Index", &index.to_string())
+}
+
+/// An HTTP stand-in for the KV endpoint of a Consul agent
+///
+/// Blocking queries with the current index bump the index and change the value of
+/// `myapp/database/port`.
+fn serve_consul() -> url::Url {
+    let index = Arc::new(AtomicU64::new(10));
+
+    common::serve(move |request| {
+        if !request.path.starts_with("/v1/kv/myapp/?") || !request.path.contains("recurse=true") {
+            return Response::new(404, "");
+        }
+
+        let current = index.load(Ordering::SeqCst);
+        if request.path.contains(&format!("index={}", current)) {
+            index.fetch_add(1, Ordering::SeqCst);
+            kv_response(current + 1, "NTQzMw==")
+        } else if current > 10 {
+            kv_response(current, "NTQzMw==")
+        } else {
+            kv_response(current, "NTQzMg==")
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_consul_source() {
+    let address = serve_consul();
+
+    let mut config = AsyncConfig::builder()
+        .load(Box::new(
+            ConsulSource::new(address.clone(), "myapp/").unwrap(),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let host = config.layers().get_view("database.host").unwrap().unwrap();
+    assert_eq!(host.value().as_str(), Some("localhost"));
+    match host.description() {
+        ConfigSourceDescription::Uri(uri) => assert!(uri.path().ends_with("myapp/database/host")),
+        other => panic!("Unexpected description: {:?}", other),
+    }
+
+    let port = config.layers().get("database.port").unwrap().unwrap();
+    assert_eq!(port.as_u16(), Some(5432));
+
+    let watch = ConsulSource::new(address, "myapp/").unwrap();
+    watch.load_async().await.unwrap();
+    assert!(watch.wait_for_change(Duration::from_secs(1)).await.unwrap());
+
+    config.reload().await.unwrap();
+    let port = config.layers().get("database.port").unwrap().unwrap();
+    assert_eq!(port.as_u16(), Some(5433));
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use config_rs_ng::Config;
+use config_rs_ng::ConfigElement;
+use config_rs_ng::ConfigElementCustomType;
+use config_rs_ng::ConfigElementListType;
+use config_rs_ng::ConfigElementMapType;
+use config_rs_ng::ConfigElementType;
+use config_rs_ng::FromConfigElement;
+use config_rs_ng::FromConfigElementError;
+use config_rs_ng::StringSource;
+
+// A format with YAML-like tags: each line is `key = value` or `key = !tag value`
+const CONFIGURATION: &str = r#"
+name = app
+password = !secret hunter2
+"#;
+
+#[derive(Debug)]
+enum TaggedValue {
+    Str(String),
+    Tagged(Tagged),
+    Map(Map),
+}
+
+// The payload of a tagged value
+#[derive(Debug)]
+struct Tagged {
+    tag: String,
+    value: String,
+}
+
+impl ConfigElementCustomType for Tagged {
+    fn type_name(&self) -> &'static str {
+        "tagged"
+    }
+}
+
+#[derive(Debug)]
+struct Map(HashMap<String, TaggedValue>);
+
+impl ConfigElementMapType for Map {
+    fn get(&self, key: &str) -> Option<&dyn ConfigElement> {
+        self.0.get(key).map(|v| v as &dyn ConfigElement)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        Box::new(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.as_str(), v as &dyn ConfigElement)),
+        )
+    }
+}
+
+impl ConfigElement for TaggedValue {
+    fn as_bool(&self) -> Option<bool> {
+        None
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            TaggedValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_list(&self) -> Option<&dyn ConfigElementListType> {
+        None
+    }
+
+    fn as_map(&self) -> Option<&dyn ConfigElementMapType> {
+        match self {
+            TaggedValue::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    fn as_custom(&self) -> Option<&dyn ConfigElementCustomType> {
+        match self {
+            TaggedValue::Tagged(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+struct TaggedFormatParser;
+
+impl config_rs_ng::FormatParser for TaggedFormatParser {
+    type Output = TaggedValue;
+
+    fn parse(buffer: Vec<u8>) -> Result<Self::Output, config_rs_ng::SourceError> {
+        let s = String::from_utf8(buffer).unwrap();
+        let map = s
+            .lines()
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(key, value)| {
+                let value = match value.strip_prefix('!').and_then(|v| v.split_once(' ')) {
+                    Some((tag, value)) => TaggedValue::Tagged(Tagged {
+                        tag: tag.to_string(),
+                        value: value.to_string(),
+                    }),
+                    None => TaggedValue::Str(value.to_string()),
+                };
+                (key.to_string(), value)
+            })
+            .collect();
+        Ok(TaggedValue::Map(Map(map)))
+    }
+}
+
+// A type that can only be constructed from a value tagged with `!secret`
+#[derive(Debug)]
+struct Secret(String);
+
+impl FromConfigElement for Secret {
+    type Error = FromConfigElementError;
+
+    fn from_config_element(element: &dyn ConfigElement) -> Result<Self, Self::Error> {
+        element
+            .as_custom()
+            .and_then(|custom| custom.downcast_ref::<Tagged>())
+            .filter(|tagged| tagged.tag == "secret")
+            .map(|tagged| Secret(tagged.value.clone()))
+            .ok_or_else(|| FromConfigElementError::TypeError {
+                expected: "secret",
+                found: element.get_type().name(),
+            })
+    }
+}
+
+fn config() -> Config {
+    Config::builder()
+        .load(Box::new(
+            StringSource::<TaggedFormatParser>::new(CONFIGURATION.to_string()).unwrap(),
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_custom_type() {
+    let config = config();
+
+    let password = config.layers().get("password").unwrap().unwrap();
+    assert_eq!(password.get_type(), ConfigElementType::Custom("tagged"));
+    assert_eq!(Secret::from_config_element(password).unwrap().0, "hunter2");
+
+    let name = config.layers().get("name").unwrap().unwrap();
+    assert!(std::matches!(
+        Secret::from_config_element(name),
+        Err(FromConfigElementError::TypeError {
+            expected: "secret",
+            found: "str"
+        })
+    ));
+}
+
+#[test]
+fn test_custom_type_errors() {
+    let config = config();
+
+    let password = config.layers().get("password").unwrap().unwrap();
+    assert!(std::matches!(
+        String::from_config_element(password),
+        Err(FromConfigElementError::TypeError {
+            expected: "str",
+            found: "tagged"
+        })
+    ));
+
+    let err = config.layers().get("password.length").unwrap_err();
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.to_string(), "Accessed tagged with key 'length'");
+}
@@ -0,0 +1,118 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use config_rs_ng::Config;
+use config_rs_ng::Datetime;
+use config_rs_ng::FromConfigElement;
+use config_rs_ng::FromConfigElementError;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::Offset;
+use config_rs_ng::StringSource;
+use config_rs_ng::TomlFormatParser;
+
+const TOML: &str = r#"
+offset = 1979-05-27T07:32:00-08:00
+local = 1979-05-27T07:32:00
+date = 1979-05-27
+time = 07:32:00.5
+"#;
+
+const JSON: &str = r#"{ "string": "1979-05-27T15:32:00Z", "invalid": "1979-13-01" }"#;
+
+fn config() -> Config {
+    Config::builder()
+        .load(Box::new(
+            StringSource::<TomlFormatParser>::new(TOML.to_string()).unwrap(),
+        ))
+        .load(Box::new(
+            StringSource::<JsonFormatParser>::new(JSON.to_string()).unwrap(),
+        ))
+        .build()
+        .unwrap()
+}
+
+fn get<T: FromConfigElement<Error = FromConfigElementError>>(
+    config: &Config,
+    key: &str,
+) -> Result<T, FromConfigElementError> {
+    T::from_config_element(config.layers().get(key).unwrap().unwrap())
+}
+
+#[test]
+fn test_toml_datetimes() {
+    let config = config();
+
+    let offset = config.layers().get("offset").unwrap().unwrap();
+    assert_eq!(offset.get_type().name(), "offset datetime");
+    let offset = offset.as_datetime().unwrap();
+    assert_eq!(offset.offset, Some(Offset::Custom { minutes: -480 }));
+    assert_eq!(offset.to_string(), "1979-05-27T07:32:00-08:00");
+
+    let types =
+        ["local", "date", "time"].map(|key| config.layers().get(key).unwrap().unwrap().get_type());
+    assert_eq!(
+        types.map(|t| t.name()),
+        ["local datetime", "local date", "local time"]
+    );
+
+    assert!(config.layers().get("date.day").is_err());
+}
+
+#[test]
+fn test_string_datetimes() {
+    let config = config();
+
+    let datetime: Datetime = get(&config, "string").unwrap();
+    assert_eq!(datetime.offset, Some(Offset::Z));
+    assert!(config
+        .layers()
+        .get("string")
+        .unwrap()
+        .unwrap()
+        .as_datetime()
+        .is_none());
+
+    assert!(std::matches!(
+        get::<Datetime>(&config, "invalid"),
+        Err(FromConfigElementError::DatetimeParseError(_))
+    ));
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono() {
+    let config = config();
+
+    let offset: chrono::DateTime<chrono::Utc> = get(&config, "offset").unwrap();
+    let string: chrono::DateTime<chrono::Utc> = get(&config, "string").unwrap();
+    assert_eq!(offset, string);
+
+    let time: chrono::NaiveTime = get(&config, "time").unwrap();
+    assert_eq!(time.to_string(), "07:32:00.500");
+    let date: chrono::NaiveDate = get(&config, "date").unwrap();
+    assert_eq!(date.to_string(), "1979-05-27");
+
+    assert!(std::matches!(
+        get::<chrono::NaiveDateTime>(&config, "offset"),
+        Err(FromConfigElementError::TypeError {
+            expected: "local datetime",
+            found: "offset datetime",
+        })
+    ));
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn test_time() {
+    let config = config();
+
+    let offset: time::OffsetDateTime = get(&config, "offset").unwrap();
+    let string: time::OffsetDateTime = get(&config, "string").unwrap();
+    assert_eq!(offset, string);
+
+    let local: time::PrimitiveDateTime = get(&config, "local").unwrap();
+    assert_eq!(local.date(), get::<time::Date>(&config, "date").unwrap());
+    assert_eq!(
+        get::<time::Time>(&config, "time").unwrap().millisecond(),
+        500
+    );
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "etcd")]
+
+mod common;
+
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+
+use config_rs_ng::AsyncConfig;
+use config_rs_ng::EtcdSource;
+use futures::StreamExt;
+
+/// Start a local etcd
+///
+/// The tests using this are ignored by default, run them with `--ignored` where `etcd` is
+/// installed.
+async fn start_etcd(data_dir: &std::path::Path) -> (common::ChildGuard, etcd_client::Client) {
+    let etcd = common::find_executable("etcd").expect("etcd not found in $PATH");
+
+    let client_url = format!("http://127.0.0.1:{}", common::free_port());
+    let peer_url = format!("http://127.0.0.1:{}", common::free_port());
+    let child = Command::new(etcd)
+        .arg("--data-dir")
+        .arg(data_dir)
+        .args(["--listen-client-urls", &client_url])
+        .args(["--advertise-client-urls", &client_url])
+        .args(["--listen-peer-urls", &peer_url])
+        .args(["--initial-advertise-peer-urls", &peer_url])
+        .args(["--initial-cluster", &format!("default={}", peer_url)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("starting etcd");
+    let child = common::ChildGuard(child);
+
+    for _ in 0..100 {
+        if let Ok(mut client) = etcd_client::Client::connect([&client_url], None).await {
+            if client.status().await.is_ok() {
+                return (child, client);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("etcd did not start");
+}
+
+#[tokio::test]
+#[ignore = "requires etcd in $PATH"]
+async fn test_etcd_source() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let (_etcd, mut client) = start_etcd(data_dir.path()).await;
+
+    client
+        .put("/myapp/database/host", "localhost", None)
+        .await
+        .unwrap();
+    client
+        .put("/myapp/database/port", "5432", None)
+        .await
+        .unwrap();
+    client.put("/otherapp/key", "value", None).await.unwrap();
+
+    let source = EtcdSource::from_client(client.clone(), "/myapp/").unwrap();
+    let mut changes = source.watch().await.unwrap();
+
+    let mut config = AsyncConfig::builder()
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap();
+
+    let host = config.layers().get("database.host").unwrap().unwrap();
+    assert_eq!(host.as_str(), Some("localhost"));
+    let port = config.layers().get("database.port").unwrap().unwrap();
+    assert_eq!(port.as_u16(), Some(5432));
+    assert!(config.layers().get("otherapp").unwrap().is_none());
+
+    client
+        .put("/myapp/database/port", "5433", None)
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(10), changes.next())
+        .await
+        .expect("waiting for change notification")
+        .expect("change stream ended")
+        .unwrap();
+
+    config.reload().await.unwrap();
+    let port = config.layers().get("database.port").unwrap().unwrap();
+    assert_eq!(port.as_u16(), Some(5433));
+}
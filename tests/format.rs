@@ -37,12 +37,12 @@ impl ConfigElementMapType for Map {
         self.0.get(key).map(|v| v as &dyn ConfigElement)
     }
 
-    fn keys(&self) -> Vec<String> {
-        self.0.keys().map(String::clone).collect()
-    }
-
-    fn values(&self) -> Vec<&dyn ConfigElement> {
-        self.0.values().map(|v| v as &dyn ConfigElement).collect()
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, &dyn ConfigElement)> + '_> {
+        Box::new(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.as_str(), v as &dyn ConfigElement)),
+        )
     }
 }
 
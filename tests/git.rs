@@ -0,0 +1,92 @@
+#![cfg(all(feature = "git", feature = "toml"))]
+
+use std::path::Path;
+
+use config_rs_ng::AsyncConfig;
+use config_rs_ng::ConfigSourceDescription;
+use config_rs_ng::GitSource;
+use config_rs_ng::TomlFormatParser;
+
+fn init(path: &Path) -> git2::Repository {
+    let mut options = git2::RepositoryInitOptions::new();
+    options.initial_head("main");
+    git2::Repository::init_opts(path, &options).unwrap()
+}
+
+fn commit_file(repository: &git2::Repository, path: &str, content: &str) -> String {
+    std::fs::write(repository.workdir().unwrap().join(path), content).unwrap();
+    let mut index = repository.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let parent = repository
+        .head()
+        .ok()
+        .map(|head| head.peel_to_commit().unwrap());
+    let parents = parent.iter().collect::<Vec<_>>();
+    repository
+        .commit(Some("HEAD"), &signature, &signature, path, &tree, &parents)
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_git_source() {
+    let directory = tempfile::tempdir().unwrap();
+    let repository = init(directory.path());
+    commit_file(&repository, "base.toml", "name = \"app\"\nport = 8080\n");
+    let first = commit_file(
+        &repository,
+        "production.toml",
+        "# Served behind a proxy\nport = 80\n",
+    );
+
+    let source = GitSource::<TomlFormatParser>::new(directory.path(), "main")
+        .unwrap()
+        .path("base.toml")
+        .path("production.toml");
+    let pinned = GitSource::<TomlFormatParser>::new(directory.path(), first.as_str())
+        .unwrap()
+        .path("production.toml");
+
+    let mut config = AsyncConfig::builder()
+        .load(Box::new(pinned))
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap();
+
+    let name = config.layers().get_view("name").unwrap().unwrap();
+    assert_eq!(name.value().as_str(), Some("app"));
+    assert_eq!(name.span().unwrap().line, 1);
+    let port = config.layers().get_view("port").unwrap().unwrap();
+    assert_eq!(port.value().as_u16(), Some(80));
+    assert_eq!(port.span().unwrap().line, 2);
+    assert_eq!(
+        port.comment().unwrap().leading.as_deref(),
+        Some("Served behind a proxy")
+    );
+    match port.description() {
+        ConfigSourceDescription::Git {
+            reference, commit, ..
+        } => {
+            assert_eq!(reference, "main");
+            assert_eq!(commit, &first);
+        }
+        other => panic!("Unexpected description: {:?}", other),
+    }
+
+    let second = commit_file(&repository, "production.toml", "port = 443\n");
+    // The working tree is not read, only the committed files
+    std::fs::write(directory.path().join("production.toml"), "port = 1\n").unwrap();
+
+    config.reload().await.unwrap();
+    let port = config.layers().get_view("port").unwrap().unwrap();
+    assert_eq!(port.value().as_u16(), Some(443));
+    match port.description() {
+        ConfigSourceDescription::Git { commit, .. } => assert_eq!(commit, &second),
+        other => panic!("Unexpected description: {:?}", other),
+    }
+}
@@ -0,0 +1,139 @@
+#![cfg(any(feature = "http", feature = "http-blocking"))]
+
+mod common;
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common::Response;
+
+/// Serve a JSON document with an ETag, counting the full (non-304) responses
+fn serve_with_etag() -> (url::Url, Arc<AtomicUsize>) {
+    let full_responses = Arc::new(AtomicUsize::new(0));
+    let counter = full_responses.clone();
+
+    let url = common::serve(move |request| match request.path.as_str() {
+        "/config.json" if request.header("if-none-match") == Some("\"v1\"") => {
+            Response::new(304, "")
+        }
+        "/config.json" => {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Response::new(200, r#"{"key": "value"}"#)
+                .header("Content-Type", "application/json")
+                .header("ETag", "\"v1\"")
+        }
+        "/config.toml" => Response::new(200, "# The key\nkey = \"value\""),
+        _ => Response::new(404, "not found"),
+    });
+
+    (url, full_responses)
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_http_source_conditional_reload() {
+    use config_rs_ng::AsyncConfig;
+    use config_rs_ng::HttpSource;
+
+    let (url, full_responses) = serve_with_etag();
+
+    let mut config = AsyncConfig::builder()
+        .load(Box::new(
+            HttpSource::new(url.join("config.json").unwrap()).unwrap(),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    config.reload().await.unwrap();
+
+    let view = config.layers().get_view("key").unwrap().unwrap();
+    assert_eq!(view.value().as_str(), Some("value"));
+    assert!(std::matches!(
+        view.description(),
+        config_rs_ng::ConfigSourceDescription::Uri(_)
+    ));
+    assert_eq!(full_responses.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(all(feature = "http", feature = "toml"))]
+#[tokio::test]
+async fn test_http_source_explicit_format() {
+    use config_rs_ng::AsyncConfig;
+    use config_rs_ng::HttpSource;
+    use config_rs_ng::TomlFormatParser;
+
+    let (url, _) = serve_with_etag();
+
+    let config = AsyncConfig::builder()
+        .load(Box::new(
+            HttpSource::new(url.join("config.toml").unwrap())
+                .unwrap()
+                .format::<TomlFormatParser>(),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let view = config.layers().get_view("key").unwrap().unwrap();
+    assert_eq!(view.value().as_str(), Some("value"));
+    assert_eq!(view.span().unwrap().line, 2);
+    assert_eq!(view.comment().unwrap().leading.as_deref(), Some("The key"));
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_http_source_errors() {
+    use config_rs_ng::AsyncConfig;
+    use config_rs_ng::HttpSource;
+    use config_rs_ng::SourceError;
+
+    let (url, _) = serve_with_etag();
+
+    let err = AsyncConfig::builder()
+        .load(Box::new(
+            HttpSource::new(url.join("missing").unwrap()).unwrap(),
+        ))
+        .build()
+        .await
+        .unwrap_err();
+    assert!(std::matches!(
+        err,
+        config_rs_ng::ConfigError::SourceError(SourceError::HttpStatus { status: 404, .. })
+    ));
+
+    let err = AsyncConfig::builder()
+        .load(Box::new(
+            HttpSource::new(url.join("config.toml").unwrap()).unwrap(),
+        ))
+        .build()
+        .await
+        .unwrap_err();
+    assert!(std::matches!(
+        err,
+        config_rs_ng::ConfigError::SourceError(SourceError::ContentType(None))
+    ));
+}
+
+#[cfg(feature = "http-blocking")]
+#[test]
+fn test_blocking_http_source_conditional_reload() {
+    use config_rs_ng::BlockingHttpSource;
+    use config_rs_ng::Config;
+
+    let (url, full_responses) = serve_with_etag();
+
+    let mut config = Config::builder()
+        .load(Box::new(
+            BlockingHttpSource::new(url.join("config.json").unwrap()).unwrap(),
+        ))
+        .build()
+        .unwrap();
+
+    config.reload().unwrap();
+
+    let value = config.layers().get("key").unwrap().unwrap();
+    assert_eq!(value.as_str(), Some("value"));
+    assert_eq!(full_responses.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "toml")]
+
+use config_rs_ng::Config;
+use config_rs_ng::ConfigConstructor;
+use config_rs_ng::ConfigSource;
+use config_rs_ng::ConfigSourceDescription;
+use config_rs_ng::SourceError;
+
+#[derive(Debug, ConfigConstructor)]
+struct Settings {
+    name: String,
+    port: u16,
+}
+
+#[derive(Debug, ConfigConstructor)]
+struct MissingField {
+    #[allow(unused)]
+    timeout: u64,
+}
+
+#[test]
+fn test_include_config() {
+    let config = Config::builder()
+        .load_default(Box::new(config_rs_ng::include_config!(
+            "tests/fixtures/defaults.toml",
+            check = Settings
+        )))
+        .build()
+        .unwrap();
+
+    let settings = Settings::construct_from(config.layers()).unwrap();
+    assert_eq!(settings.name, "defaults");
+    assert_eq!(settings.port, 8080);
+
+    let view = config.layers().get_view("database.user").unwrap().unwrap();
+    assert_eq!(view.value().as_str(), Some("app"));
+    match view.description() {
+        ConfigSourceDescription::Path(p) => assert!(p.ends_with("tests/fixtures/defaults.toml")),
+        other => panic!("Unexpected description: {:?}", other),
+    }
+}
+
+#[test]
+fn test_include_config_check_fails() {
+    let source = config_rs_ng::include_config!(
+        "tests/fixtures/defaults.toml",
+        format = toml,
+        check = MissingField,
+    );
+    assert!(std::matches!(
+        source.load(),
+        Err(SourceError::Validation(_))
+    ));
+}
+
+#[test]
+fn test_include_config_compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/include_config_*.rs");
+}
@@ -0,0 +1,127 @@
+//! Conformance of the number handling of all format backends
+//!
+//! Every backend must return a number from exactly those sized accessors whose type can hold the
+//! number.
+
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use config_rs_ng::Config;
+use config_rs_ng::ConfigElement;
+use config_rs_ng::FormatParser;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::StringSource;
+use config_rs_ng::TomlFormatParser;
+
+/// Integers that every backend can represent
+const INTEGERS: &[i128] = &[
+    0,
+    1,
+    -1,
+    i8::MAX as i128,
+    i8::MIN as i128,
+    u8::MAX as i128,
+    u8::MAX as i128 + 1,
+    i16::MIN as i128 - 1,
+    u16::MAX as i128,
+    i32::MAX as i128,
+    i32::MIN as i128,
+    u32::MAX as i128,
+    (1 << 53) + 1,
+    i64::MAX as i128,
+    i64::MIN as i128,
+];
+
+const FLOATS: &[f64] = &[0.5, -1.5, 65536.25, 1e300, -1e39];
+
+/// Load `document` with parser `P`
+fn load<P>(document: String) -> Config
+where
+    P: FormatParser + 'static,
+    P::Output: 'static,
+{
+    Config::builder()
+        .load(Box::new(StringSource::<P>::new(document).unwrap()))
+        .build()
+        .unwrap()
+}
+
+fn check_integer(format: &str, element: &dyn ConfigElement, n: i128) {
+    let context = format!("{} for {}", format, n);
+    assert_eq!(element.as_i8(), i8::try_from(n).ok(), "i8 {}", context);
+    assert_eq!(element.as_i16(), i16::try_from(n).ok(), "i16 {}", context);
+    assert_eq!(element.as_i32(), i32::try_from(n).ok(), "i32 {}", context);
+    assert_eq!(element.as_i64(), i64::try_from(n).ok(), "i64 {}", context);
+    assert_eq!(element.as_i128(), Some(n), "i128 {}", context);
+    assert_eq!(element.as_u8(), u8::try_from(n).ok(), "u8 {}", context);
+    assert_eq!(element.as_u16(), u16::try_from(n).ok(), "u16 {}", context);
+    assert_eq!(element.as_u32(), u32::try_from(n).ok(), "u32 {}", context);
+    assert_eq!(element.as_u64(), u64::try_from(n).ok(), "u64 {}", context);
+    assert_eq!(
+        element.as_u128(),
+        u128::try_from(n).ok(),
+        "u128 {}",
+        context
+    );
+
+    let exact_f64 = Some(n as f64).filter(|f| *f as i128 == n);
+    assert_eq!(element.as_f64(), exact_f64, "f64 {}", context);
+    let exact_f32 = Some(n as f32).filter(|f| *f as i128 == n);
+    assert_eq!(element.as_f32(), exact_f32, "f32 {}", context);
+}
+
+fn check_float(format: &str, element: &dyn ConfigElement, f: f64) {
+    let context = format!("{} for {}", format, f);
+    assert_eq!(element.as_i64(), None, "i64 {}", context);
+    assert_eq!(element.as_u64(), None, "u64 {}", context);
+    assert_eq!(element.as_f64(), Some(f), "f64 {}", context);
+
+    let in_range = (f32::MIN as f64..=f32::MAX as f64).contains(&f);
+    assert_eq!(
+        element.as_f32(),
+        Some(f as f32).filter(|_| in_range),
+        "f32 {}",
+        context
+    );
+}
+
+fn conformance(format: &str, load: impl Fn(&str) -> Config) {
+    for n in INTEGERS {
+        let config = load(&n.to_string());
+        let element = config.layers().get("value").unwrap().unwrap();
+        check_integer(format, element, *n);
+    }
+
+    for f in FLOATS {
+        let config = load(&format!("{:?}", f));
+        let element = config.layers().get("value").unwrap().unwrap();
+        check_float(format, element, *f);
+    }
+}
+
+#[test]
+fn test_json() {
+    conformance("json", |value| {
+        load::<JsonFormatParser>(format!(r#"{{ "value": {} }}"#, value))
+    });
+
+    let config = load::<JsonFormatParser>(format!(r#"{{ "value": {} }}"#, u64::MAX));
+    let element = config.layers().get("value").unwrap().unwrap();
+    check_integer("json", element, u64::MAX.into());
+    assert_eq!(element.get_type().name(), "u64");
+}
+
+#[test]
+fn test_toml() {
+    conformance("toml", |value| {
+        load::<TomlFormatParser>(format!("value = {}", value))
+    });
+}
+
+#[test]
+fn test_types() {
+    let config = load::<JsonFormatParser>(r#"{ "i8": 127, "i16": 255, "f32": 0.5 }"#.to_string());
+    for key in ["i8", "i16", "f32"] {
+        let element = config.layers().get(key).unwrap().unwrap();
+        assert_eq!(element.get_type().name(), key);
+    }
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "redis")]
+
+mod common;
+
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+
+use config_rs_ng::AsyncConfig;
+use config_rs_ng::RedisSource;
+use futures::StreamExt;
+
+/// Start a local redis-server
+///
+/// The tests using this are ignored by default, run them with `--ignored` where `redis-server` is
+/// installed.
+async fn start_redis() -> (common::ChildGuard, url::Url) {
+    let redis_server =
+        common::find_executable("redis-server").expect("redis-server not found in $PATH");
+
+    let port = common::free_port();
+    let child = Command::new(redis_server)
+        .args(["--port", &port.to_string()])
+        .args(["--save", ""])
+        .args(["--notify-keyspace-events", "KA"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("starting redis-server");
+    let child = common::ChildGuard(child);
+
+    let url = url::Url::parse(&format!("redis://127.0.0.1:{}/0", port)).unwrap();
+    let client = redis::Client::open(url.clone()).unwrap();
+    for _ in 0..100 {
+        if client.get_async_connection().await.is_ok() {
+            return (child, url);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("redis-server did not start");
+}
+
+async fn query(url: &url::Url, cmd: &mut redis::Cmd) {
+    let client = redis::Client::open(url.clone()).unwrap();
+    let mut connection = client.get_async_connection().await.unwrap();
+    cmd.query_async::<_, ()>(&mut connection).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires redis-server in $PATH"]
+async fn test_redis_hash_source() {
+    let (_redis, url) = start_redis().await;
+
+    query(
+        &url,
+        redis::cmd("HSET")
+            .arg("myapp:toggles")
+            .arg("ui.dark_mode")
+            .arg("true")
+            .arg("beta.enabled")
+            .arg("false"),
+    )
+    .await;
+
+    let source = RedisSource::hash(url.clone(), "myapp:toggles").unwrap();
+    let mut changes = source.watch().await.unwrap();
+
+    let mut config = AsyncConfig::builder()
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap();
+
+    let dark_mode = config.layers().get("ui.dark_mode").unwrap().unwrap();
+    assert_eq!(dark_mode.as_bool(), Some(true));
+    let beta = config.layers().get("beta.enabled").unwrap().unwrap();
+    assert_eq!(beta.as_bool(), Some(false));
+
+    query(
+        &url,
+        redis::cmd("HSET")
+            .arg("myapp:toggles")
+            .arg("beta.enabled")
+            .arg("true"),
+    )
+    .await;
+    tokio::time::timeout(Duration::from_secs(5), changes.next())
+        .await
+        .expect("no keyspace notification")
+        .unwrap()
+        .unwrap();
+
+    config.reload().await.unwrap();
+    let beta = config.layers().get("beta.enabled").unwrap().unwrap();
+    assert_eq!(beta.as_bool(), Some(true));
+}
+
+#[tokio::test]
+#[ignore = "requires redis-server in $PATH"]
+async fn test_redis_pattern_source() {
+    let (_redis, url) = start_redis().await;
+
+    query(
+        &url,
+        redis::cmd("MSET")
+            .arg("myapp:database:host")
+            .arg("localhost")
+            .arg("myapp:database:port")
+            .arg("6379")
+            .arg("otherapp:key")
+            .arg("value"),
+    )
+    .await;
+    query(&url, redis::cmd("RPUSH").arg("myapp:queue").arg("job")).await;
+
+    let config = AsyncConfig::builder()
+        .load(Box::new(RedisSource::pattern(url, "myapp:*").unwrap()))
+        .build()
+        .await
+        .unwrap();
+
+    let host = config.layers().get("database.host").unwrap().unwrap();
+    assert_eq!(host.as_str(), Some("localhost"));
+    let port = config.layers().get("database.port").unwrap().unwrap();
+    assert_eq!(port.as_u16(), Some(6379));
+    assert!(config.layers().get("key").unwrap().is_none());
+    assert!(config.layers().get("queue").unwrap().is_none());
+}
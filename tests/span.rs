@@ -0,0 +1,44 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use std::io::Write;
+
+use config_rs_ng::Config;
+use config_rs_ng::ConfigSourceDescription;
+use config_rs_ng::FileSource;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::TomlFormatParser;
+
+#[test]
+fn test_spans_of_layers() {
+    let mut toml = tempfile::NamedTempFile::new().unwrap();
+    toml.write_all(b"# defaults\n[server]\nport = 8080\nhost = \"localhost\"\n")
+        .unwrap();
+
+    let mut json = tempfile::NamedTempFile::new().unwrap();
+    json.write_all(b"{\n  \"server\": {\n    \"port\": 9090\n  }\n}\n")
+        .unwrap();
+
+    let config = Config::builder()
+        .load(Box::new(
+            FileSource::<TomlFormatParser>::new(toml.path()).unwrap(),
+        ))
+        .load(Box::new(
+            FileSource::<JsonFormatParser>::new(json.path()).unwrap(),
+        ))
+        .build()
+        .unwrap();
+
+    let view = config.layers().get_view("server.port").unwrap().unwrap();
+    assert_eq!(view.value().as_u16(), Some(9090));
+    let span = view.span().unwrap();
+    assert_eq!(span.to_string(), "3:13");
+    match view.description() {
+        ConfigSourceDescription::Path(p) => assert_eq!(p, json.path()),
+        other => panic!("Unexpected description: {:?}", other),
+    }
+
+    let view = config.layers().get_view("server.host").unwrap().unwrap();
+    let span = view.span().unwrap();
+    assert_eq!((span.line, span.column), (4, 8));
+    assert_eq!(span.end - span.start, "\"localhost\"".len());
+}
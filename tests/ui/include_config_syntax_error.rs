@@ -0,0 +1,4 @@
+// The path is relative to the crate trybuild generates in target/tests/trybuild
+fn main() {
+    let _ = config_rs_ng::include_config!("../../../../tests/fixtures/invalid.toml");
+}
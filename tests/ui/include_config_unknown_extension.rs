@@ -0,0 +1,3 @@
+fn main() {
+    let _ = config_rs_ng::include_config!("config.ini");
+}
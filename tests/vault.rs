@@ -0,0 +1,178 @@
+#![cfg(feature = "vault")]
+
+mod common;
+
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+
+use common::Response;
+use config_rs_ng::AsyncConfig;
+use config_rs_ng::ConfigSourceDescription;
+use config_rs_ng::VaultAuth;
+use config_rs_ng::VaultSource;
+
+/// An HTTP stand-in for the AppRole login and KV v2 endpoints of a Vault server
+fn serve_vault() -> url::Url {
+    common::serve(
+        |request| match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/v1/auth/approle/login") => Response::new(
+                200,
+                serde_json::json!({
+                    "auth": {
+                        "client_token": "s.token",
+                        "lease_duration": 3600,
+                        "renewable": true,
+                    }
+                })
+                .to_string(),
+            ),
+            ("GET", "/v1/secret/data/myapp/db")
+                if request.header("x-vault-token") == Some("s.token") =>
+            {
+                Response::new(
+                    200,
+                    serde_json::json!({
+                        "lease_duration": 0,
+                        "renewable": false,
+                        "data": {
+                            "data": { "username": "app", "password": "hunter2" },
+                            "metadata": { "version": 1 },
+                        }
+                    })
+                    .to_string(),
+                )
+            }
+            ("GET", _) => Response::new(403, r#"{"errors": ["permission denied"]}"#),
+            _ => Response::new(404, ""),
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_vault_source_approle() {
+    let address = serve_vault();
+    let auth = VaultAuth::AppRole {
+        role_id: "role".to_string(),
+        secret_id: "approle-secret-id".to_string(),
+    };
+    let source = VaultSource::new(address, auth)
+        .unwrap()
+        .secret("database", "myapp/db");
+    assert!(!format!("{:?}", source).contains("approle-secret-id"));
+
+    let config = AsyncConfig::builder()
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap();
+
+    let password = config
+        .layers()
+        .get_view("database.password")
+        .unwrap()
+        .unwrap();
+    assert_eq!(password.value().as_str(), Some("hunter2"));
+    match password.description() {
+        ConfigSourceDescription::Uri(uri) => {
+            assert_eq!(uri.path(), "/v1/secret/data/myapp/db");
+            assert!(!uri.as_str().contains("hunter2"));
+        }
+        other => panic!("Unexpected description: {:?}", other),
+    }
+
+    let username = config.layers().get("database.username").unwrap().unwrap();
+    assert_eq!(username.as_str(), Some("app"));
+}
+
+#[tokio::test]
+async fn test_vault_source_leases() {
+    use config_rs_ng::AsyncConfigSource;
+
+    let address = serve_vault();
+    let auth = VaultAuth::AppRole {
+        role_id: "role".to_string(),
+        secret_id: "approle-secret-id".to_string(),
+    };
+    let source = VaultSource::new(address, auth)
+        .unwrap()
+        .secret("database", "myapp/db");
+    assert!(source.reload_due().is_none());
+
+    source.load_async().await.unwrap();
+    let leases = source.leases();
+    assert_eq!(leases.len(), 1);
+    assert_eq!(leases[0].path, "auth/approle/login");
+    assert_eq!(leases[0].duration, Duration::from_secs(3600));
+    assert!(source.reload_due().is_some());
+}
+
+#[tokio::test]
+async fn test_vault_source_permission_denied() {
+    let address = serve_vault();
+    let source = VaultSource::new(address, VaultAuth::Token("wrong".to_string()))
+        .unwrap()
+        .secret("database", "myapp/db");
+
+    let err = AsyncConfig::builder()
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap_err();
+    assert!(std::matches!(
+        err,
+        config_rs_ng::ConfigError::SourceError(config_rs_ng::SourceError::HttpStatus {
+            status: 403,
+            ..
+        })
+    ));
+}
+
+/// Runs against a local `vault server -dev`, run it with `--ignored` where `vault` is installed
+#[tokio::test]
+#[ignore = "requires vault in $PATH"]
+async fn test_vault_dev_server() {
+    let vault = common::find_executable("vault").expect("vault not found in $PATH");
+
+    let address = format!("127.0.0.1:{}", common::free_port());
+    let url = url::Url::parse(&format!("http://{}/", address)).unwrap();
+    let _server = common::ChildGuard(
+        Command::new(&vault)
+            .args(["server", "-dev", "-dev-root-token-id=root"])
+            .arg(format!("-dev-listen-address={}", address))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("starting vault"),
+    );
+
+    let mut written = false;
+    for _ in 0..100 {
+        let status = Command::new(&vault)
+            .args(["kv", "put", "secret/myapp/db", "password=hunter2"])
+            .env("VAULT_ADDR", url.as_str())
+            .env("VAULT_TOKEN", "root")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        if status.success() {
+            written = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(written, "vault did not start");
+
+    let source = VaultSource::new(url, VaultAuth::Token("root".to_string()))
+        .unwrap()
+        .secret("database", "myapp/db");
+    let config = AsyncConfig::builder()
+        .load(Box::new(source))
+        .build()
+        .await
+        .unwrap();
+
+    let password = config.layers().get("database.password").unwrap().unwrap();
+    assert_eq!(password.as_str(), Some("hunter2"));
+}
@@ -0,0 +1,85 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+use config_rs_ng::Config;
+use config_rs_ng::JsonFormatParser;
+use config_rs_ng::StringSource;
+use config_rs_ng::TomlFormatParser;
+
+const DEFAULTS: &str = r#"
+    [server]
+    host = "localhost"
+    port = 8080
+
+    [[routes]]
+    path = "/"
+"#;
+
+const OVERRIDES: &str = r#"
+    { "server": { "port": 9090, "token": "secret-abc" } }
+"#;
+
+fn config() -> Config {
+    Config::builder()
+        .load(Box::new(
+            StringSource::<TomlFormatParser>::new(DEFAULTS.to_string()).unwrap(),
+        ))
+        .load(Box::new(
+            StringSource::<JsonFormatParser>::new(OVERRIDES.to_string()).unwrap(),
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_flatten_layers() {
+    let config = config();
+
+    let lines: Vec<String> = config
+        .layers()
+        .flatten()
+        .map(|(path, value)| match value.as_str() {
+            Some(s) => format!("{} = {}", path, s),
+            None => format!("{} = {}", path, value.as_i64().unwrap()),
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "server.port = 9090",
+            "server.token = secret-abc",
+            "server.host = localhost",
+            "routes.0.path = /",
+        ]
+    );
+
+    // Every yielded path leads to the yielded value
+    for (path, value) in config.layers().walk() {
+        let found = config.layers().get_with_accessor(path).unwrap().unwrap();
+        assert!(std::ptr::eq(
+            found as *const _ as *const u8,
+            value as *const _ as *const u8
+        ));
+    }
+}
+
+#[test]
+fn test_walk_lint() {
+    let config = config();
+
+    let secrets: Vec<String> = config
+        .layers()
+        .walk()
+        .filter(|(_, value)| std::matches!(value.as_str(), Some(s) if s.starts_with("secret-")))
+        .map(|(path, _)| path.to_string())
+        .collect();
+    assert_eq!(secrets, ["server.token"]);
+
+    let keys = config
+        .layers()
+        .walk()
+        .filter(|(path, _)| {
+            std::matches!(path.steps().last(), Some(config_rs_ng::AccessType::Key(_)))
+        })
+        .count();
+    assert_eq!(keys, 6);
+}